@@ -10,6 +10,8 @@ pub use core::compile_error as std_compile_error;
 pub use core::concat as std_concat;
 #[doc(hidden)]
 pub use core::stringify as std_stringify;
+#[doc(hidden)]
+pub use core::marker::PhantomData as std_PhantomData;
 
 /// Parses (optional) generics and (optional) subsequent where clause.
 ///
@@ -17,6 +19,8 @@ pub use core::stringify as std_stringify;
 ///
 /// ```ignore
 /// $callback_macro { $($callback_macro_args)* }
+/// $( else $error_callback_macro { $($error_callback_macro_args)* } | recover )?
+/// $( until [ $($terminators)+ ] )?
 /// $(
 ///     < $generics >
 ///     $( $tokens_between_generics_and_where_clause )*
@@ -25,7 +29,7 @@ pub use core::stringify as std_stringify;
 ///     )?
 /// )?
 /// $(
-///     $( ; | { $($body)* } )
+///     $( ; | { $($body)* } | $terminator )
 ///     $($remaining_tokens)*
 /// )?
 /// ```
@@ -40,19 +44,75 @@ pub use core::stringify as std_stringify;
 ///     [ $( where $where_clause )? ]
 ///     $($( $tokens_between_generics_and_where_clause )*)?
 ///     $(
-///         $( ; | { $($body)* } )
+///         $( ; | { $($body)* } | $terminator )
 ///         $($remaining_tokens)*
 ///     )?
 /// }
 /// ```
 ///
+/// The where clause normally ends at the first `;` or `{ ... }` outside of `<...>`. An
+/// `until [...]` list adds more terminators, each a single token tree such as `=`, `=>` or a
+/// keyword, so `parse! { cb {} until [=] <T> where T: Clone = Vec<T>; }` stops before
+/// `= Vec<T>;` and forwards it unchanged. The terminators `=`, `=>`, `->`, `|`, `in` and `as`
+/// are recognized while the where clause is scanned. Any other terminator, such as a custom
+/// keyword, is matched with a local helper macro, so that form must be expanded in item
+/// position.
+///
+/// Malformed input expands to a `compile_error!` describing the problem and quoting the
+/// offending tokens, followed by a second error that rustc points at the last offending token:
+///
+/// ```compile_fail
+/// macro_rules! tr {
+///     ([$($g:tt)*] [$($r:tt)*] [$($w:tt)*]) => { pub trait Tr $($g)* $($w)* { } };
+/// }
+///
+/// // unexpected token '+', expected ':', '=', ',', or '>'
+/// generics2::parse! { tr {} <T + Clone> }
+/// ```
+///
+/// ```compile_fail
+/// # macro_rules! tr {
+/// #     ([$($g:tt)*] [$($r:tt)*] [$($w:tt)*]) => { pub trait Tr $($g)* $($w)* { } };
+/// # }
+/// // missing '>' after '< T : Clone'
+/// generics2::parse! { tr {} <T: Clone }
+/// ```
+///
+/// With an `else` clause the error is passed to the error callback instead, so that the calling
+/// macro can word its own diagnostic or fall back to another expansion:
+///
+/// ```ignore
+/// $error_callback_macro! {
+///     $( $error_callback_macro_args )*
+///     [ $kind ]
+///     [ $($offending_tokens)* ]
+///     [ $( [ $([ $generics_parsed_so_far ])* ] )? ]
+///     [ $($message)* ]
+///     [ $($rest)* ]
+/// }
+/// ```
+///
+/// where `$kind` is one of `unexpected_token`, `missing_angle_bracket`, `missing_colon` and
+/// `missing_const_type`, the third bracket is laid out like the first bracket of
+/// [`parse_raw`](parse_raw), and `compile_error!($($message)*)` reproduces the default error.
+/// The last bracket is the input left after the offending tokens, starting after the closing
+/// `>` for an error in the generics, and with the tokens in front of `where`, `where` itself
+/// and the predicates parsed before the error put back for an error in the where clause, so
+/// that the generics parsed so far followed by these tokens can be parsed again.
+///
+/// With `recover` the callback is still called on error, with the generics accepted before the
+/// error and the rest of the input parsed again that way, and with the macro calls emitting
+/// the errors appended after the remaining tokens. A callback that forwards its remaining
+/// tokens thus keeps the item alive next to the errors, which lets IDEs still resolve it while
+/// the generics are being edited.
+///
 /// # Examples
 ///
 /// ```rust
 /// pub trait TheTrait { }
 ///
 /// #[doc(hidden)]
-/// pub use generics::parse as generics_parse;
+/// pub use generics2::parse as generics_parse;
 /// #[doc(hidden)]
 /// pub use std::compile_error as std_compile_error;
 ///
@@ -81,18 +141,63 @@ pub use core::stringify as std_stringify;
 ///         );
 ///     };
 /// }
+///
+/// pub struct Foo<'a, T: 'a>(&'a T);
+///
+/// impl_the_trait! { Foo<'a, T: 'a> where T: Clone }
+/// # fn main() { }
 /// ```
 #[macro_export]
 macro_rules! parse {
+    (
+        $callback:path { $($callback_args:tt)* } recover until [$($terminator:tt)+] $($token:tt)*
+    ) => {
+        $crate::parse_raw! {
+            $crate::parse_callback { @parse $callback [$($callback_args)*] }
+            else $crate::recover_impl {
+                [$crate::parse_callback] [@parse $callback [$($callback_args)*]] [until [$($terminator)+]] []
+            }
+            until [$($terminator)+] $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* } recover $($token:tt)*
+    ) => {
+        $crate::parse_raw! {
+            $crate::parse_callback { @parse $callback [$($callback_args)*] }
+            else $crate::recover_impl { [$crate::parse_callback] [@parse $callback [$($callback_args)*]] [] [] }
+            $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        else $error_callback:path { $($error_callback_args:tt)* }
+        $($token:tt)*
+    ) => {
+        $crate::parse_raw! {
+            $crate::else_impl {
+                @on_error [$error_callback] [$($error_callback_args)*] [$crate::parse_callback] [@parse $callback [$($callback_args)*]]
+            }
+            $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* } until [$($terminator:tt)+] $($token:tt)*
+    ) => {
+        $crate::until_impl! {
+            [$crate::parse_raw] [$crate::parse_callback] [@parse $callback [$($callback_args)*]]
+            [$($terminator)+] [$($token)*]
+        }
+    };
     (
         $callback:path { $($callback_args:tt)* } < $($token:tt)*
     ) => {
-        $crate::parse_generics_impl! { [$crate::parse_callback] [$callback [$($callback_args)*]] [] [] [$($token)*] }
+        $crate::parse_generics_impl! { [$crate::parse_callback] [@parse $callback [$($callback_args)*]] [] [] [] [$($token)*] }
     };
     (
         $callback:path { $($callback_args:tt)* } $($token:tt)*
     ) => {
-        $crate::allow_where_clause_impl! { [$crate::parse_callback] [$callback [$($callback_args)*]] [] [$($token)*] }
+        $crate::allow_where_clause_impl! { [$crate::parse_callback] [@parse $callback [$($callback_args)*]] [] [$($token)*] }
     };
 }
 
@@ -101,7 +206,7 @@ macro_rules! parse {
 #[macro_export]
 macro_rules! parse_callback {
     (
-        $callback:path
+        @parse $callback:path
         [$($callback_args:tt)*]
         [
             [$([$([$($g:tt)*])*])?]
@@ -121,12 +226,350 @@ macro_rules! parse_callback {
     };
 }
 
+/// Splits the input of `parse` and `parse_raw` at the first top-level terminator
+///
+/// The terminators `=`, `=>`, `->`, `|`, `in` and `as` are looked up by the where clause
+/// scanners, from one slot each in the `@until` callback arguments. Any other terminator needs
+/// a local helper macro that splits the input before it is parsed.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! until_impl {
+    (
+        [$parse:path] [$callback:path] [$($callback_args:tt)*] [$($terminator:tt)+] [$($token:tt)*]
+    ) => {
+        $crate::until_impl! {
+            @known [[] [] [] [] [] []] [$parse] [$callback] [$($callback_args)*]
+            [$($terminator)+] [$($terminator)+] [$($token)*]
+        }
+    };
+    (
+        @known [$c0:tt $($c:tt)*] [$parse:path] [$callback:path] [$($callback_args:tt)*]
+        [$($terminator:tt)+] [= $($known:tt)*] [$($token:tt)*]
+    ) => {
+        $crate::until_impl! {
+            @known [[=] $($c)*] [$parse] [$callback] [$($callback_args)*]
+            [$($terminator)+] [$($known)*] [$($token)*]
+        }
+    };
+    (
+        @known [$c0:tt $c1:tt $($c:tt)*] [$parse:path] [$callback:path] [$($callback_args:tt)*]
+        [$($terminator:tt)+] [=> $($known:tt)*] [$($token:tt)*]
+    ) => {
+        $crate::until_impl! {
+            @known [$c0 [=>] $($c)*] [$parse] [$callback] [$($callback_args)*]
+            [$($terminator)+] [$($known)*] [$($token)*]
+        }
+    };
+    (
+        @known [$c0:tt $c1:tt $c2:tt $($c:tt)*] [$parse:path] [$callback:path] [$($callback_args:tt)*]
+        [$($terminator:tt)+] [-> $($known:tt)*] [$($token:tt)*]
+    ) => {
+        $crate::until_impl! {
+            @known [$c0 $c1 [->] $($c)*] [$parse] [$callback] [$($callback_args)*]
+            [$($terminator)+] [$($known)*] [$($token)*]
+        }
+    };
+    (
+        @known [$c0:tt $c1:tt $c2:tt $c3:tt $($c:tt)*] [$parse:path] [$callback:path] [$($callback_args:tt)*]
+        [$($terminator:tt)+] [| $($known:tt)*] [$($token:tt)*]
+    ) => {
+        $crate::until_impl! {
+            @known [$c0 $c1 $c2 [|] $($c)*] [$parse] [$callback] [$($callback_args)*]
+            [$($terminator)+] [$($known)*] [$($token)*]
+        }
+    };
+    (
+        @known [$c0:tt $c1:tt $c2:tt $c3:tt $c4:tt $($c:tt)*] [$parse:path] [$callback:path] [$($callback_args:tt)*]
+        [$($terminator:tt)+] [in $($known:tt)*] [$($token:tt)*]
+    ) => {
+        $crate::until_impl! {
+            @known [$c0 $c1 $c2 $c3 [in] $($c)*] [$parse] [$callback] [$($callback_args)*]
+            [$($terminator)+] [$($known)*] [$($token)*]
+        }
+    };
+    (
+        @known [$c0:tt $c1:tt $c2:tt $c3:tt $c4:tt $c5:tt $($c:tt)*] [$parse:path] [$callback:path] [$($callback_args:tt)*]
+        [$($terminator:tt)+] [as $($known:tt)*] [$($token:tt)*]
+    ) => {
+        $crate::until_impl! {
+            @known [$c0 $c1 $c2 $c3 $c4 [as] $($c)*] [$parse] [$callback] [$($callback_args)*]
+            [$($terminator)+] [$($known)*] [$($token)*]
+        }
+    };
+    (
+        @known [$($c:tt)*] [$parse:path] [$callback:path] [$($callback_args:tt)*]
+        [$($terminator:tt)+] [; $($known:tt)*] [$($token:tt)*]
+    ) => {
+        $crate::until_impl! {
+            @known [$($c)*] [$parse] [$callback] [$($callback_args)*]
+            [$($terminator)+] [$($known)*] [$($token)*]
+        }
+    };
+    (
+        @known [$($c:tt)*] [$parse:path] [$callback:path] [$($callback_args:tt)*]
+        [$($terminator:tt)+] [] [$($token:tt)*]
+    ) => {
+        $parse ! {
+            $crate::until_impl { @until [$($c)*] [$callback] [$($callback_args)*] }
+            $($token)*
+        }
+    };
+    (
+        @known [$($c:tt)*] [$parse:path] [$callback:path] [$($callback_args:tt)*]
+        [$($terminator:tt)+] [$($known:tt)*] [$($token:tt)*]
+    ) => {
+        $crate::until_impl! {
+            @define [$] [$parse] [$callback] [$($callback_args)*] [$($terminator)+] [$($token)*]
+        }
+    };
+    (
+        @define [$dollar:tt] [$parse:path] [$callback:path] $callback_args:tt
+        [$($terminator:tt)+] [$($token:tt)*]
+    ) => {
+        macro_rules! __generics2_until {
+            $(
+                ([] [$dollar($head:tt)*] [$terminator $dollar($tail:tt)*]) => {
+                    $parse ! {
+                        $crate::until_impl { @tail [$callback] $callback_args [$terminator $dollar($tail)*] }
+                        $dollar($head)*
+                    }
+                };
+            )+
+            ([] [$dollar($head:tt)*] [; $dollar($tail:tt)*]) => {
+                $parse ! {
+                    $crate::until_impl { @tail [$callback] $callback_args [] }
+                    $dollar($head)* ; $dollar($tail)*
+                }
+            };
+            ([] [$dollar($head:tt)*] [{ $dollar($body:tt)* } $dollar($tail:tt)*]) => {
+                $parse ! {
+                    $crate::until_impl { @tail [$callback] $callback_args [] }
+                    $dollar($head)* { $dollar($body)* } $dollar($tail)*
+                }
+            };
+            ([$dollar($depth:tt)*] [$dollar($head:tt)*] [< $dollar($tail:tt)*]) => {
+                __generics2_until! { [< $dollar($depth)*] [$dollar($head)* <] [$dollar($tail)*] }
+            };
+            ([$dollar($depth:tt)*] [$dollar($head:tt)*] [<< $dollar($tail:tt)*]) => {
+                __generics2_until! { [< < $dollar($depth)*] [$dollar($head)* <<] [$dollar($tail)*] }
+            };
+            ([< $dollar($depth:tt)*] [$dollar($head:tt)*] [> $dollar($tail:tt)*]) => {
+                __generics2_until! { [$dollar($depth)*] [$dollar($head)* >] [$dollar($tail)*] }
+            };
+            ([< < $dollar($depth:tt)*] [$dollar($head:tt)*] [>> $dollar($tail:tt)*]) => {
+                __generics2_until! { [$dollar($depth)*] [$dollar($head)* >>] [$dollar($tail)*] }
+            };
+            ([$dollar($depth:tt)*] [$dollar($head:tt)*] [$dollar next:tt $dollar($tail:tt)*]) => {
+                __generics2_until! { [$dollar($depth)*] [$dollar($head)* $dollar next] [$dollar($tail)*] }
+            };
+            ([$dollar($depth:tt)*] [$dollar($head:tt)*] []) => {
+                $parse ! {
+                    $crate::until_impl { @tail [$callback] $callback_args [] }
+                    $dollar($head)*
+                }
+            };
+        }
+        __generics2_until! { [] [] [$($token)*] }
+    };
+    (
+        @until [$($c:tt)*] [$callback:path] [$($callback_args:tt)*] $($parsed:tt)*
+    ) => {
+        $callback ! { $($callback_args)* $($parsed)* }
+    };
+    (
+        @tail [$callback:path] [$($callback_args:tt)*] [$($tail:tt)*] $($parsed:tt)*
+    ) => {
+        $callback ! { $($callback_args)* $($parsed)* $($tail)* }
+    };
+}
+
+/// Forwards a successful parse to the callback that was given next to an error callback
+#[doc(hidden)]
+#[macro_export]
+macro_rules! else_impl {
+    (
+        @on_error [$error_callback:path] [$($error_callback_args:tt)*]
+        [$callback:path] [$($callback_args:tt)*] $($parsed:tt)*
+    ) => {
+        $callback ! { $($callback_args)* $($parsed)* }
+    };
+}
+
+/// Skips to the last of the offending tokens of a parse error and has no rule for it, so that
+/// rustc points a second error at that token
+#[doc(hidden)]
+#[macro_export]
+macro_rules! error_at_impl {
+    () => {};
+    (
+        [$($last:tt)*] $t0:tt $t1:tt $t2:tt $t3:tt $($token:tt)+
+    ) => {
+        $crate::error_at_impl! { [$t3] $($token)+ }
+    };
+    (
+        [$($last:tt)*] $next:tt $($token:tt)*
+    ) => {
+        $crate::error_at_impl! { [$next] $($token)* }
+    };
+    (
+        [$($last:tt)*]
+    ) => {
+        $crate::error_at_impl! { $($last)* }
+    };
+}
+
+/// Reports a parse error to the error callback found in the callback arguments, if any,
+/// or as a compile error otherwise
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_error_impl {
+    (
+        [@on_error $($on_error:tt)*]
+        [$kind:ident] [$($token:tt)*] [$($partial:tt)*] [@generics $($rest:tt)*] $($message:tt)*
+    ) => {
+        $crate::parse_error_impl! {
+            @skip [] [$($rest)*]
+            [@on_error $($on_error)*] [$kind] [$($token)*] [$($partial)*] $($message)*
+        }
+    };
+    (
+        [@on_error [$error_callback:path] [$($error_callback_args:tt)*] $($other:tt)*]
+        [$kind:ident] [$($token:tt)*] [$($partial:tt)*] [$($rest:tt)*] $($message:tt)*
+    ) => {
+        $error_callback ! {
+            $($error_callback_args)*
+            [$kind] [$($token)*] [$($partial)*] [$($message)*] [$($rest)*]
+        }
+    };
+    (
+        [@done [$callback:path] [$($callback_args:tt)*] [$($g:tt)*] $r:tt $s:tt $d:tt $a:tt $f:tt [$($inter:tt)*]]
+        [$kind:ident] [$($token:tt)*] [$($partial:tt)*] [$($rest:tt)*] $($message:tt)*
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [$kind] [$($token)*] [$($g)*] [$($inter)* where $($rest)*] $($message)*
+        }
+    };
+    (
+        [@until [$($c:tt)*] [$callback:path] [$($callback_args:tt)*]]
+        [$kind:ident] [$($token:tt)*] [$($partial:tt)*] [$($rest:tt)*] $($message:tt)*
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [$kind] [$($token)*] [$($partial)*] [$($rest)*] $($message)*
+        }
+    };
+    (
+        [@tail [$callback:path] [$($callback_args:tt)*] $($other:tt)*]
+        [$kind:ident] [$($token:tt)*] [$($partial:tt)*] [$($rest:tt)*] $($message:tt)*
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [$kind] [$($token)*] [$($partial)*] [$($rest)*] $($message)*
+        }
+    };
+    (
+        [$($callback_args:tt)*]
+        [$kind:ident] [$($token:tt)*] [$($partial:tt)*] [$($rest:tt)*] $($message:tt)*
+    ) => {
+        $crate::std_compile_error!($($message)*);
+        $crate::error_at_impl! { [] $($token)* }
+    };
+    (
+        @skip [$($depth:tt)*] [< $($rest:tt)*] $($other:tt)*
+    ) => {
+        $crate::parse_error_impl! { @skip [< $($depth)*] [$($rest)*] $($other)* }
+    };
+    (
+        @skip [$($depth:tt)*] [<< $($rest:tt)*] $($other:tt)*
+    ) => {
+        $crate::parse_error_impl! { @skip [< < $($depth)*] [$($rest)*] $($other)* }
+    };
+    (
+        @skip [< $($depth:tt)*] [> $($rest:tt)*] $($other:tt)*
+    ) => {
+        $crate::parse_error_impl! { @skip [$($depth)*] [$($rest)*] $($other)* }
+    };
+    (
+        @skip [< < $($depth:tt)*] [>> $($rest:tt)*] $($other:tt)*
+    ) => {
+        $crate::parse_error_impl! { @skip [$($depth)*] [$($rest)*] $($other)* }
+    };
+    (
+        @skip [<] [>> $($rest:tt)*] $args:tt $kind:tt $token:tt $partial:tt $($message:tt)*
+    ) => {
+        $crate::parse_error_impl! { $args $kind $token $partial [$($rest)*] $($message)* }
+    };
+    (
+        @skip [] [> $($rest:tt)*] $args:tt $kind:tt $token:tt $partial:tt $($message:tt)*
+    ) => {
+        $crate::parse_error_impl! { $args $kind $token $partial [$($rest)*] $($message)* }
+    };
+    (
+        @skip [] [>> $($rest:tt)*] $args:tt $kind:tt $token:tt $partial:tt $($message:tt)*
+    ) => {
+        $crate::parse_error_impl! { $args $kind $token $partial [$($rest)*] $($message)* }
+    };
+    (
+        @skip [$($depth:tt)*] [$x:tt $($rest:tt)*] $($other:tt)*
+    ) => {
+        $crate::parse_error_impl! { @skip [$($depth)*] [$($rest)*] $($other)* }
+    };
+    (
+        @skip [$($depth:tt)*] [] $args:tt $kind:tt $token:tt $partial:tt $($message:tt)*
+    ) => {
+        $crate::parse_error_impl! { $args $kind $token $partial [] $($message)* }
+    };
+}
+
+/// Error callback for the `recover` form of `parse` and `parse_raw`, that parses the generics
+/// accepted before the error and the rest of the input again, and passes the errors collected
+/// so far after the remaining tokens
+#[doc(hidden)]
+#[macro_export]
+macro_rules! recover_impl {
+    (
+        $callback:tt $callback_args:tt $until:tt $error:tt
+        $kind:tt $token:tt [[]] $message:tt $rest:tt
+    ) => {
+        $crate::recover_impl! {
+            $callback $callback_args $until $error
+            $kind $token [] $message $rest
+        }
+    };
+    (
+        [$callback:path] [$($callback_args:tt)*] [$($until:tt)*] [$($error:tt)*]
+        [$kind:ident] [$($token:tt)*] [$([$([$($g:tt)*])*])?] [$($message:tt)*] [$($rest:tt)*]
+    ) => {
+        $crate::parse_raw! {
+            $crate::until_impl {
+                @tail [$callback] [$($callback_args)*]
+                [
+                    $($error)*
+                    $crate::std_compile_error!($($message)*);
+                    $crate::error_at_impl! { [] $($token)* }
+                ]
+            }
+            else $crate::recover_impl {
+                [$callback] [$($callback_args)*] [$($until)*]
+                [
+                    $($error)*
+                    $crate::std_compile_error!($($message)*);
+                    $crate::error_at_impl! { [] $($token)* }
+                ]
+            }
+            $($until)*
+            $(< $($($g)*),* >)?
+            $($rest)*
+        }
+    };
+}
+
 /// Parses (optional) generics and (optional) subsequent where clause, keeping the structure of the parsed information.
 ///
 /// This macro accepts an input in the following form:
 ///
 /// ```ignore
 /// $callback_macro { $($callback_macro_args)* }
+/// $( else $error_callback_macro { $($error_callback_macro_args)* } | recover )?
+/// $( until [ $($terminators)+ ] )?
 /// $(
 ///     < $generics >
 ///     $( $tokens_between_generics_and_where_clause )*
@@ -135,7 +578,7 @@ macro_rules! parse_callback {
 ///     )?
 /// )?
 /// $(
-///     $( ; | { $($body)* } )
+///     $( ; | { $($body)* } | $terminator )
 ///     $($remaining_tokens)*
 /// )?
 /// ```
@@ -149,11 +592,17 @@ macro_rules! parse_callback {
 ///        [ $( [ $([ $generics ])* ] )? ]
 ///        [ $( [ $([ $generics_without_constraints ]) ] )? ]
 ///        [ $( where $where_clause )? ]
+///        [ $([ $kind $name [ $($bounds)* ] [ $($default)* ] ])* ]
+///        [ $([ [ $($bounded_ty)* ] [ $($bounds)* ] ])* ]
+///        [ $( [ $([ $generics_with_defaults ])* ] )? ]
+///        [ $([ $($attributes)* ])* ]
+///        [ $([ $( sized | unsized )? ])* ]
+///        [ $( $where_relaxed_name )* ]
 ///        $($extra_reserved_for_future_expansion:tt)*
 ///     ]
 ///     $($( $tokens_between_generics_and_where_clause )*)?
 ///     $(
-///         $( ; | { $($body)* } )
+///         $( ; | { $($body)* } | $terminator )
 ///         $($remaining_tokens)*
 ///     )?
 /// }
@@ -163,13 +612,54 @@ macro_rules! parse_callback {
 /// to use a wildcard match rather than an exact match, allowing to add new data
 /// in future crate versions without breaking compatibility.
 ///
+/// The fourth bracket lists every generic parameter with its constraints and default value
+/// split into separate fields, where `$kind` is one of `lifetime`, `type`, or `const`.
+/// For `const` parameters the `$bounds` field holds the parameter type, so
+/// `<'a: 'b, T: Clone = (), const N: usize>` becomes
+/// `[lifetime 'a ['b] []] [type T [Clone] [()]] [const N [usize] []]`.
+/// The type may be any path, and the default a literal or a block, so
+/// `<const M: crate::Mode, const B: bool = { cfg!(x) }>` becomes
+/// `[const M [crate::Mode] []] [const B [bool] [{ cfg!(x) }]]`. A const parameter without
+/// a type is rejected.
+///
+/// The fifth bracket holds the where clause split into predicates. Predicates are separated
+/// on top-level commas only, so commas inside `<...>`, `(...)` and `for<...>` stay intact, and
+/// `where T: Into<(u8, u16)>, 'a: 'b` becomes `[[T] [Into<(u8, u16)>]] [['a] ['b]]`.
+///
+/// The body starts at the first `;` or `{ ... }` outside of `<...>`, so brace-delimited const
+/// arguments such as `Foo<{ N * 2 }>` may appear both between the generics and the where
+/// clause and inside the predicates. An `until [...]` list adds terminators, an `else`
+/// clause reports errors to a callback, and `recover` still calls the callback on error, as in
+/// [`parse`](parse).
+///
+/// The sixth bracket is the declaration form of the generics: it is laid out like the first
+/// one, but keeps the default values, so `<T: Clone = (), const N: usize = 4>` becomes
+/// `[[T: Clone = ()] [const N: usize = 4]]`. Use it to re-declare a type with the same public
+/// API, and the first bracket for `impl` headers, where defaults are not allowed.
+///
+/// The seventh bracket holds the attributes of each generic parameter, in the same order as
+/// the fourth one, so `<#[cfg(feature = "x")] T, U>` gives `[#[cfg(feature = "x")]] []`.
+/// Attributes are also kept in front of their parameter in the first and sixth brackets, but
+/// not in the second one: rustc rejects attributes on generic arguments, so even `#[cfg]`
+/// cannot be kept at the use site.
+///
+/// The eighth bracket holds one flag per generic parameter, again in the order of the fourth
+/// one: `sized` or `unsized` for type parameters, and nothing for lifetimes and consts. A type
+/// parameter is `unsized` when it is relaxed with `?Sized` inline, so `<T: ?Sized, U, 'a>`
+/// gives `[unsized] [sized] []`.
+///
+/// The ninth bracket lists the names relaxed with `?Sized` by a where clause predicate on the
+/// bare parameter, so `<T, U> where U: ?Sized` gives `[U]`. They are not folded into the
+/// flags, because matching a name to its parameter would need a local helper macro and
+/// `parse_raw!` must also expand inside `impl` and `trait` blocks.
+///
 /// # Examples
 ///
 /// ```rust
 /// pub trait TheTrait { }
 ///
 /// #[doc(hidden)]
-/// pub use generics::parse as generics_parse;
+/// pub use generics2::parse_raw as generics_parse_raw;
 /// #[doc(hidden)]
 /// pub use std::compile_error as std_compile_error;
 ///
@@ -178,7 +668,7 @@ macro_rules! parse_callback {
 ///     (
 ///         $name:ident $($token:tt)*
 ///     ) => {
-///         $crate::generics_parse! {
+///         $crate::generics_parse_raw! {
 ///             $crate::impl_the_trait {
 ///                 @impl $name
 ///             }
@@ -198,13 +688,53 @@ macro_rules! parse_callback {
 ///         );
 ///     };
 /// }
+///
+/// pub struct Foo<'a, T: 'a>(&'a T);
+///
+/// impl_the_trait! { Foo<'a, T: 'a> where T: Clone }
+/// # fn main() { }
 /// ```
 #[macro_export]
 macro_rules! parse_raw {
+    (
+        $callback:path { $($callback_args:tt)* } recover until [$($terminator:tt)+] $($token:tt)*
+    ) => {
+        $crate::parse_raw! {
+            $callback { $($callback_args)* }
+            else $crate::recover_impl { [$callback] [$($callback_args)*] [until [$($terminator)+]] [] }
+            until [$($terminator)+] $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* } recover $($token:tt)*
+    ) => {
+        $crate::parse_raw! {
+            $callback { $($callback_args)* }
+            else $crate::recover_impl { [$callback] [$($callback_args)*] [] [] }
+            $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        else $error_callback:path { $($error_callback_args:tt)* }
+        $($token:tt)*
+    ) => {
+        $crate::parse_raw! {
+            $crate::else_impl {
+                @on_error [$error_callback] [$($error_callback_args)*] [$callback] [$($callback_args)*]
+            }
+            $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* } until [$($terminator:tt)+] $($token:tt)*
+    ) => {
+        $crate::until_impl! { [$crate::parse_raw] [$callback] [$($callback_args)*] [$($terminator)+] [$($token)*] }
+    };
     (
         $callback:path { $($callback_args:tt)* } < $($token:tt)*
     ) => {
-        $crate::parse_generics_impl! { [$callback] [$($callback_args)*] [] [] [$($token)*] }
+        $crate::parse_generics_impl! { [$callback] [$($callback_args)*] [] [] [] [$($token)*] }
     };
     (
         $callback:path { $($callback_args:tt)* } $($token:tt)*
@@ -221,12 +751,65 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
-        [const $param:ident $($token:tt)*]
+        [$($s:tt)*]
+        [$(# [$($attr:tt)*])* const $param:ident : = $($token:tt)*]
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_const_type] [$param] [[$($g)*]] [@generics $($token)*]
+            $crate::std_concat!(
+                "missing type for const parameter '",
+                $crate::std_stringify!($param),
+                "'"
+            )
+        }
+    };
+    (
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [$(# [$($attr:tt)*])* const $param:ident : , $($token:tt)*]
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_const_type] [$param] [[$($g)*]] [@generics $($token)*]
+            $crate::std_concat!(
+                "missing type for const parameter '",
+                $crate::std_stringify!($param),
+                "'"
+            )
+        }
+    };
+    (
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [$(# [$($attr:tt)*])* const $param:ident : > $($token:tt)*]
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_const_type] [$param] [[$($g)*]] [$($token)*]
+            $crate::std_concat!(
+                "missing type for const parameter '",
+                $crate::std_stringify!($param),
+                "'"
+            )
+        }
+    };
+    (
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [$(# [$($attr:tt)*])* const $param:ident : $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
-            @param
-            [[const $param] [$param]]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            @constrained_param [:]
+            [[const $param] [$param] [[$(# [$($attr)*])*] [] const $param]]
+            [] []
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [$($token)*]
         }
     };
@@ -235,12 +818,30 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
-        [$param:ident $($token:tt)*]
+        [$($s:tt)*]
+        [$(# [$($attr:tt)*])* const $param:ident $($token:tt)*]
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_const_type] [$param] [[$($g)*]] [@generics $($token)*]
+            $crate::std_concat!(
+                "missing type for const parameter '",
+                $crate::std_stringify!($param),
+                "'"
+            )
+        }
+    };
+    (
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [$(# [$($attr:tt)*])* $param:ident $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! { 
             @param
-            [[$param] [$param]]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] 
+            [[$param] [$param] [[$(# [$($attr)*])*] [sized] type $param]]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*] 
             [$($token)*]
         }
     };
@@ -249,12 +850,13 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
-        [$param:lifetime $($token:tt)*]
+        [$($s:tt)*]
+        [$(# [$($attr:tt)*])* $param:lifetime $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! { 
             @param
-            [[$param] [$param]]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] 
+            [[$param] [$param] [[$(# [$($attr)*])*] [] lifetime $param]]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*] 
             [$($token)*]
         }
     };
@@ -263,6 +865,7 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [> $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
@@ -270,7 +873,8 @@ macro_rules! parse_generics_impl {
             [$callback] [$($callback_args)*]
             [$($g)*]
             [$($r)*]
-            []
+            [$($s)*]
+            [] []
             [$($token)*]
         }
     };
@@ -279,68 +883,79 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [$x:tt $($token:tt)*]
     ) => {
-        $crate::std_compile_error!($crate::std_concat!(
-            "unexpected token '",
-            $crate::std_stringify!($x),
-            "', expected ident, or lifetime"
-        ));
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [unexpected_token] [$x] [[$($g)*]] [@generics $x $($token)*]
+            $crate::std_concat!(
+                "unexpected token '",
+                $crate::std_stringify!($x),
+                "', expected ident, or lifetime"
+            )
+        }
     };
     (
         [$callback:path]
         [$($callback_args:tt)*]
         [$($([$($g:tt)*])+)?]
         [$($r:tt)*]
+        [$($s:tt)*]
         []
     ) => {
-        $crate::std_compile_error!($crate::std_concat!(
-            "missing '>' after '",
-            $crate::std_stringify!( < $($($($g)*),+ ,)? ),
-            "'"
-        ));
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_angle_bracket] [$($($($g)*)+)?] [[$($([$($g)*])+)?]] []
+            $crate::std_concat!(
+                "missing '>' after '",
+                $crate::std_stringify!( < $($($($g)*),+ ,)? ),
+                "'"
+            )
+        }
     };
     (
         @param
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ : $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @constrained_param [:]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [] []
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [$($token)*]
         }
     };
     (
         @param
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ = $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @constrained_param [=]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [] []
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] 
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*] 
             [$($token)*]
         }
     };
     (
         @param
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ > $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
@@ -348,17 +963,19 @@ macro_rules! parse_generics_impl {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)*]]
             [$($r)* [$($rparam)*]]
-            []
+            [$($s)* [$($sparam)* [] []]]
+            [] []
             [$($token)*]
         }
     };
     (
         @param
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ >> $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
@@ -366,17 +983,19 @@ macro_rules! parse_generics_impl {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)*]]
             [$($r)* [$($rparam)*]]
-            []
+            [$($s)* [$($sparam)* [] []]]
+            [] []
             [ > $($token)*]
         }
     };
     (
         @param
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ , > $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
@@ -384,17 +1003,19 @@ macro_rules! parse_generics_impl {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)*]]
             [$($r)* [$($rparam)*]]
-            []
+            [$($s)* [$($sparam)* [] []]]
+            [] []
             [$($token)*]
         }
     };
     (
         @param
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ , >> $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
@@ -402,102 +1023,116 @@ macro_rules! parse_generics_impl {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)*]]
             [$($r)* [$($rparam)*]]
-            []
+            [$($s)* [$($sparam)* [] []]]
+            [] []
             [ > $($token)*]
         }
     };
     (
         @param
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ , $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)*]]
             [$($r)* [$($rparam)*]]
+            [$($s)* [$($sparam)* [] []]]
             [$($token)*]
         }
     };
     (
         @param
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [$x:tt $($token:tt)*]
     ) => {
-        $crate::std_compile_error!($crate::std_concat!(
-            "unexpected token '",
-            $crate::std_stringify!($x),
-            "', expected ':', '=', ',', or '>'"
-        ));
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [unexpected_token] [$x] [[$($g)*]] [@generics $x $($token)*]
+            $crate::std_concat!(
+                "unexpected token '",
+                $crate::std_stringify!($x),
+                "', expected ':', '=', ',', or '>'"
+            )
+        }
     };
     (
         @param
-        [[$([$($gparam:tt)*])*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($([$($g:tt)*])+)?]
         [$($r:tt)*]
+        [$($s:tt)*]
         []
     ) => {
-        $crate::std_compile_error!($crate::std_concat!(
-            "missing '>' after '",
-            $crate::std_stringify!( < $($($($g)*),+ ,)? $([$($gparam)*])* ),
-            "'"
-        ));
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_angle_bracket] [$($gparam)*] [[$($([$($g)*])+)?]] []
+            $crate::std_concat!(
+                "missing '>' after '",
+                $crate::std_stringify!( < $($($($g)*),+ ,)? $($gparam)* ),
+                "'"
+            )
+        }
     };
     (
         @constrained_param [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ < $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @angles_in_constraint [$kind]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)*]
             [] []
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] 
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*] 
             [$($token)*]
         }
     };
     (
         @constrained_param [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ << $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @angles_in_constraint [$kind]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)*]
             [] []
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [ < $($token)*]
         }
     };
     (
         @constrained_param [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ > $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
@@ -505,18 +1140,20 @@ macro_rules! parse_generics_impl {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)* : $($constraint)*]]
             [$($r)* [$($rparam)*]]
-            []
+            [$($s)* [$($sparam)* [$($constraint)*] [$($value)*]]]
+            [] []
             [$($token)*]
         }
     };
     (
         @constrained_param [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ >> $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
@@ -524,18 +1161,20 @@ macro_rules! parse_generics_impl {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)* : $($constraint)*]]
             [$($r)* [$($rparam)*]]
-            []
+            [$($s)* [$($sparam)* [$($constraint)*] [$($value)*]]]
+            [] []
             [ > $($token)*]
         }
     };
     (
         @constrained_param [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ , > $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
@@ -543,18 +1182,20 @@ macro_rules! parse_generics_impl {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)* : $($constraint)*]]
             [$($r)* [$($rparam)*]]
-            []
+            [$($s)* [$($sparam)* [$($constraint)*] [$($value)*]]]
+            [] []
             [$($token)*]
         }
     };
     (
         @constrained_param [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ , >> $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
@@ -562,100 +1203,130 @@ macro_rules! parse_generics_impl {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)* : $($constraint)*]]
             [$($r)* [$($rparam)*]]
-            []
+            [$($s)* [$($sparam)* [$($constraint)*] [$($value)*]]]
+            [] []
             [ > $($token)*]
         }
     };
     (
         @constrained_param [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ , $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             [$callback] [$($callback_args)*]
             [$($g)* [$($gparam)* : $($constraint)*]]
             [$($r)* [$($rparam)*]]
+            [$($s)* [$($sparam)* [$($constraint)*] [$($value)*]]]
             [$($token)*]
         }
     };
     (
         @constrained_param [:]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ = $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @constrained_param [=]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)*]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
+            [$($token)*]
+        }
+    };
+    (
+        @constrained_param [:]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [[$($attr:tt)*] [sized] $($sparam:tt)*]]
+        [$($constraint:tt)*] [$($value:tt)*]
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [ ? $($token:tt)*]
+    ) => {
+        $crate::parse_generics_impl! {
+            @constrained_param [:]
+            [[$($gparam)*] [$($rparam)*] [[$($attr)*] [unsized] $($sparam)*]]
+            [$($constraint)* ?] [$($value)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [$($token)*]
         }
     };
     (
         @constrained_param [:]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ $x:tt $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @constrained_param [:]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)* $x] [$($value)*]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [$($token)*]
         }
     };
     (
         @constrained_param [=]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ $x:tt $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @constrained_param [=]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)* $x]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [$($token)*]
         }
     };
     (
         @constrained_param [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($($constraint:tt)+)?] [$($($value:tt)+)?]
         [$callback:path]
         [$($callback_args:tt)*]
-        [$($g:tt)*]
+        [$($([$($g:tt)*])+)?]
         [$($r:tt)*]
+        [$($s:tt)*]
         []
     ) => {
-        $crate::std_compile_error!($crate::std_concat!(
-            "missing '>' after '",
-            $crate::std_stringify!( < $($($($g)*),+ ,)? $($gparam)* $( : $($constraint)+)? $( = $($value)+)? ),
-            "'"
-        ));
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_angle_bracket]
+            [$($gparam)* $(: $($constraint)+)? $(= $($value)+)?] [[$($([$($g)*])+)?]] []
+            $crate::std_concat!(
+                "missing '>' after '",
+                $crate::std_stringify!( < $($($($g)*),+ ,)? $($gparam)* $( : $($constraint)+)? $( = $($value)+)? ),
+                "'"
+            )
+        }
     };
     (
         @angles_in_constraint [:]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
         []
@@ -663,19 +1334,20 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ > $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @constrained_param [:]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)* < $($inside_angles)* > ] [$($value)*]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [$($token)*]
         }
     };
     (
         @angles_in_constraint [=]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
         []
@@ -683,19 +1355,20 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ > $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @constrained_param [=]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)* < $($inside_angles)* > ]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [$($token)*]
         }
     };
     (
         @angles_in_constraint [:]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
         []
@@ -703,19 +1376,20 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ >> $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @constrained_param [:]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)* < $($inside_angles)* > ] [$($value)*]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [ > $($token)*]
         }
     };
     (
         @angles_in_constraint [=]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
         []
@@ -723,19 +1397,20 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ >> $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @constrained_param [=]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)* < $($inside_angles)* > ]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [ > $($token)*]
         }
     };
     (
         @angles_in_constraint [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
         [[$($parent_level:tt)*] $([$($outer_levels:tt)*])*]
@@ -743,21 +1418,22 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ > $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @angles_in_constraint [$kind]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)*]
             [$($parent_level)* < $($inside_angles)* > ]
             [$([$($outer_levels)*])*]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [$($token)*]
         }
     };
     (
         @angles_in_constraint [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
         [[$($parent_level:tt)*] $([$($outer_levels:tt)*])*]
@@ -765,21 +1441,22 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ >> $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @angles_in_constraint [$kind]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)*]
             [$($parent_level)* < $($inside_angles)* > ]
             [$([$($outer_levels)*])*]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] 
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*] 
             [ > $($token)*]
         }
     };
     (
         @angles_in_constraint [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
         [$([$($outer_levels:tt)*])*]
@@ -787,21 +1464,22 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ < $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @angles_in_constraint [$kind]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)*]
             []
-            [[$($inside_angles:tt)*] $([$($outer_levels)*])*]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*]
+            [[$($inside_angles)*] $([$($outer_levels)*])*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*]
             [$($token)*]
         }
     };
     (
         @angles_in_constraint [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
         [$([$($outer_levels:tt)*])*]
@@ -809,21 +1487,22 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [ << $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @angles_in_constraint [$kind]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)*]
             []
-            [[$($inside_angles:tt)*] $([$($outer_levels)*])*]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] 
+            [[$($inside_angles)*] $([$($outer_levels)*])*]
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*] 
             [ < $($token)*]
         }
     };
     (
         @angles_in_constraint [$kind:tt]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
         [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
         [$([$($outer_levels:tt)*])*]
@@ -831,77 +1510,110 @@ macro_rules! parse_generics_impl {
         [$($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         [$x:tt $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @angles_in_constraint [$kind]
-            [[$($gparam)*] [$($rparam)*]]
+            [[$($gparam)*] [$($rparam)*] [$($sparam)*]]
             [$($constraint)*] [$($value)*]
             [$($inside_angles)* $x]
             [$([$($outer_levels)*])*]
-            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] 
+            [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*] 
             [$($token)*]
         }
     };
     (
-        @angles_in_constraint [:]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
-        [$($constraint:tt)*] [$($($value:tt)+)?]
+        @angles_in_constraint [$kind:tt]
+        [[$($gparam:tt)*] [$($rparam:tt)*] [$($sparam:tt)*]]
+        [$($constraint:tt)*] [$($value:tt)*]
         [$($inside_angles:tt)*]
-        [$([$($outer_levels:tt)*])*]
+        [$($outer_levels:tt)*]
         [$callback:path]
         [$($callback_args:tt)*]
-        [$($([$($g:tt)*])+)?]
+        [$($g:tt)*]
         [$($r:tt)*]
+        [$($s:tt)*]
         []
     ) => {
-        $crate::std_compile_error!($crate::std_concat!(
-            "missing '>' after '",
-            $crate::std_stringify!(
-                < $($($($g)*),+ ,)? $($gparam)*
-                : $($constraint)* $( < $($outer_levels)* )* < $($inside_angles)*
-                $( = $($value)+)?
-            ),
-            "'"
-        ));
+        $crate::parse_generics_impl! {
+            @unclosed_angles [$kind]
+            [$($gparam)*] [$($constraint)*] [$($value)*]
+            [< $($inside_angles)*] [$($outer_levels)*]
+            [$($callback_args)*] [$($g)*]
+        }
     };
     (
-        @angles_in_constraint [=]
-        [[$($gparam:tt)*] [$($rparam:tt)*]]
-        [$($($constraint:tt)+)?] [$($value:tt)*]
-        [$($inside_angles:tt)*]
-        [$([$($outer_levels:tt)*])*]
-        [$callback:path]
-        [$($callback_args:tt)*]
-        [$($([$($g:tt)*])+)?]
-        [$($r:tt)*]
-        []
+        @unclosed_angles [$kind:tt]
+        $gparam:tt $constraint:tt $value:tt
+        [$($open:tt)*] [[$($parent_level:tt)*] $($outer_levels:tt)*]
+        $callback_args:tt $g:tt
     ) => {
-        $crate::std_compile_error!($crate::std_concat!(
-            "missing '>' after '",
-            $crate::std_stringify!(
-                < $($($($g)*),+ ,)? $($gparam)*
-                $( : $($constraint)+)?
-                = $($value)* $( < $($outer_levels)* )* < $($inside_angles)*
-            ),
-            "'"
-        ));
+        $crate::parse_generics_impl! {
+            @unclosed_angles [$kind]
+            $gparam $constraint $value
+            [< $($parent_level)* $($open)*] [$($outer_levels)*]
+            $callback_args $g
+        }
+    };
+    (
+        @unclosed_angles [:]
+        [$($gparam:tt)*] [$($constraint:tt)*] [$($($value:tt)+)?]
+        [$($open:tt)*] []
+        [$($callback_args:tt)*] [$($([$($g:tt)*])+)?]
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_angle_bracket]
+            [$($gparam)* : $($constraint)* $($open)* $(= $($value)+)?] [[$($([$($g)*])+)?]] []
+            $crate::std_concat!(
+                "missing '>' after '",
+                $crate::std_stringify!(
+                    < $($($($g)*),+ ,)? $($gparam)* : $($constraint)* $($open)* $(= $($value)+)?
+                ),
+                "'"
+            )
+        }
+    };
+    (
+        @unclosed_angles [=]
+        [$($gparam:tt)*] [$($($constraint:tt)+)?] [$($value:tt)*]
+        [$($open:tt)*] []
+        [$($callback_args:tt)*] [$($([$($g:tt)*])+)?]
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_angle_bracket]
+            [$($gparam)* $(: $($constraint)+)? = $($value)* $($open)*] [[$($([$($g)*])+)?]] []
+            $crate::std_concat!(
+                "missing '>' after '",
+                $crate::std_stringify!(
+                    < $($($($g)*),+ ,)? $($gparam)* $(: $($constraint)+)? = $($value)* $($open)*
+                ),
+                "'"
+            )
+        }
     };
     (
         @done
         [$callback:path]
         [$($callback_args:tt)*]
-        [$($g:tt)*]
+        [$([$($g:tt)*])*]
         [$($r:tt)*]
-        [$($inter:tt)*]
+        [$([[$($attr:tt)*] [$($flag:tt)*] $kind:tt $name:tt [$($bounds:tt)*] [$($($default:tt)+)?]])*]
+        [] [$($inter:tt)*]
         [ ; $($token:tt)*]
     ) => {
         $callback ! {
             $($callback_args)*
             [
-                [ [$($g)*] ]
+                [ [$([$($attr)* $($g)*])*] ]
                 [ [$($r)*] ]
                 []
+                [$([$kind $name [$($bounds)*] [$($($default)+)?]])*]
+                []
+                [ [$([$($attr)* $($g)* $(= $($default)+)?])*] ]
+                [$([$($attr)*])*]
+                [$([$($flag)*])*]
+                []
                 $crate $crate
             ]
             $($inter)* ; $($token)*
@@ -911,17 +1623,24 @@ macro_rules! parse_generics_impl {
         @done
         [$callback:path]
         [$($callback_args:tt)*]
-        [$($g:tt)*]
+        [$([$($g:tt)*])*]
         [$($r:tt)*]
-        [$($inter:tt)*]
+        [$([[$($attr:tt)*] [$($flag:tt)*] $kind:tt $name:tt [$($bounds:tt)*] [$($($default:tt)+)?]])*]
+        [] [$($inter:tt)*]
         [ $( { $($body:tt)* } $($token:tt)* )? ]
     ) => {
         $callback ! {
             $($callback_args)*
             [
-                [ [$($g)*] ]
+                [ [$([$($attr)* $($g)*])*] ]
                 [ [$($r)*] ]
                 []
+                [$([$kind $name [$($bounds)*] [$($($default)+)?]])*]
+                []
+                [ [$([$($attr)* $($g)* $(= $($default)+)?])*] ]
+                [$([$($attr)*])*]
+                [$([$($flag)*])*]
+                []
                 $crate $crate
             ]
             $($inter)* $( { $($body)* } $($token)* )?
@@ -931,226 +1650,6186 @@ macro_rules! parse_generics_impl {
         @done
         [$callback:path]
         [$($callback_args:tt)*]
-        [$($g:tt)*]
+        [$([$($g:tt)*])*]
         [$($r:tt)*]
-        [$($inter:tt)*]
+        [$([[$($attr:tt)*] [$($flag:tt)*] $kind:tt $name:tt [$($bounds:tt)*] [$($($default:tt)+)?]])*]
+        [] [$($inter:tt)*]
         [where $($token:tt)*]
     ) => {
         $crate::parse_where_clause_impl! {
             [$callback]
             [$($callback_args)*]
-            [ [$($g)*] ]
+            [ [$([$($attr)* $($g)*])*] ]
             [ [$($r)*] ]
-            [] [$($inter)*] [$($token)*]
+            [$([$kind $name [$($bounds)*] [$($($default)+)?]])*]
+            [ [$([$($attr)* $($g)* $(= $($default)+)?])*] ]
+            [$([$($attr)*])*]
+            [$([$($flag)*])*]
+            [$($inter)*] [$($token)*]
         }
     };
     (
         @done
         [$callback:path]
-        [$($callback_args:tt)*]
+        [@until [[=] $($c:tt)*] $($callback_args:tt)*]
         [$($g:tt)*]
         [$($r:tt)*]
-        [$($inter:tt)*]
-        [$token:tt $($other_tokens:tt)*]
+        [$($s:tt)*]
+        [] [$($inter:tt)*]
+        [ = $($token:tt)*]
     ) => {
         $crate::parse_generics_impl! {
             @done
-            [$callback] [$($callback_args)*]
+            [$callback] [@until [[=] $($c)*] $($callback_args)*]
             [$($g)*]
             [$($r)*]
-            [$($inter)* $token]
-            [$($other_tokens)*]
+            [$($s)*]
+            [] [$($inter)*]
+            [where = $($token)*]
         }
     };
-}
-
-#[doc(hidden)]
-#[macro_export]
-macro_rules! parse_where_clause_impl {
     (
+        @done
         [$callback:path]
-        [$($callback_args:tt)*]
-        [$($g:tt)*] [$($r:tt)*]
-        [$($($w:tt)+)?]
-        [$($inter:tt)*] 
-        [ ; $($token:tt)* ]
+        [@until [$c0:tt [=>] $($c:tt)*] $($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [] [$($inter:tt)*]
+        [ => $($token:tt)*]
     ) => {
-        $callback ! { 
-            $($callback_args)*
-            [
-                [$($g)*]
-                [$($r)*]
-                [$(where $($w)+)?]
-                $crate $crate
-            ]
-            $($inter)* ; $($token)*
+        $crate::parse_generics_impl! {
+            @done
+            [$callback] [@until [$c0 [=>] $($c)*] $($callback_args)*]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [] [$($inter)*]
+            [where => $($token)*]
         }
     };
     (
+        @done
         [$callback:path]
-        [$($callback_args:tt)*]
-        [$($g:tt)*] [$($r:tt)*]
-        [$($($w:tt)+)?]
-        [$($inter:tt)*] 
-        [ $( { $($body:tt)* } $($token:tt)* )? ]
+        [@until [$c0:tt $c1:tt [->] $($c:tt)*] $($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [] [$($inter:tt)*]
+        [ -> $($token:tt)*]
     ) => {
-        $callback ! { 
-            $($callback_args)*
-            [
-                [$($g)*]
-                [$($r)*]
-                [$(where $($w)+)?]
-                $crate $crate
-            ]
-            $($inter)* $( { $($body)* } $($token)* )?
+        $crate::parse_generics_impl! {
+            @done
+            [$callback] [@until [$c0 $c1 [->] $($c)*] $($callback_args)*]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [] [$($inter)*]
+            [where -> $($token)*]
         }
     };
     (
+        @done
         [$callback:path]
-        [$($callback_args:tt)*]
-        [$($g:tt)*] [$($r:tt)*]
-        [$($w:tt)*]
-        [$($inter:tt)*] 
-        [$token:tt $($other_tokens:tt)*]
+        [@until [$c0:tt $c1:tt $c2:tt [|] $($c:tt)*] $($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [] [$($inter:tt)*]
+        [ | $($token:tt)*]
     ) => {
-        $crate::parse_where_clause_impl! { 
-            [$callback]
-            [$($callback_args)*]
-            [$($g)*] [$($r)*]
-            [$($w)* $token]
-            [$($inter)*]
-            [$($other_tokens)*]
+        $crate::parse_generics_impl! {
+            @done
+            [$callback] [@until [$c0 $c1 $c2 [|] $($c)*] $($callback_args)*]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [] [$($inter)*]
+            [where | $($token)*]
         }
     };
-}
-
-#[doc(hidden)]
-#[macro_export]
-macro_rules! allow_where_clause_impl {
     (
+        @done
         [$callback:path]
-        [$($callback_args:tt)*]
-        [$($inter:tt)*]
-        [ ; $($token:tt)*]
+        [@until [$c0:tt $c1:tt $c2:tt $c3:tt [in] $($c:tt)*] $($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [] [$($inter:tt)*]
+        [ in $($token:tt)*]
     ) => {
-        $callback ! {
-            $($callback_args)*
-            [
-                []
-                []
-                []
-                $crate $crate
-            ]
-            $($inter)* ; $($token)*
+        $crate::parse_generics_impl! {
+            @done
+            [$callback] [@until [$c0 $c1 $c2 $c3 [in] $($c)*] $($callback_args)*]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [] [$($inter)*]
+            [where in $($token)*]
         }
     };
     (
+        @done
         [$callback:path]
-        [$($callback_args:tt)*]
-        [$($inter:tt)*]
-        [ $( { $($body:tt)* } $($token:tt)* )? ]
+        [@until [$c0:tt $c1:tt $c2:tt $c3:tt $c4:tt [as] $($c:tt)*] $($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [] [$($inter:tt)*]
+        [ as $($token:tt)*]
     ) => {
-        $callback ! {
-            $($callback_args)*
-            [
-                []
-                []
-                []
-                $crate $crate
-            ]
-            $($inter)* $( { $($body)* } $($token)* )?
+        $crate::parse_generics_impl! {
+            @done
+            [$callback] [@until [$c0 $c1 $c2 $c3 $c4 [as] $($c)*] $($callback_args)*]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [] [$($inter)*]
+            [where as $($token)*]
         }
     };
     (
+        @done
         [$callback:path]
         [$($callback_args:tt)*]
-        [$($inter:tt)*]
-        [where $($token:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [$($depth:tt)*] [$($inter:tt)*]
+        [ < $($token:tt)*]
     ) => {
-        $crate::parse_where_clause_impl! { 
-            [$callback]
-            [$($callback_args)*]
-            [] []
-            []
-            [$($inter)*]
+        $crate::parse_generics_impl! {
+            @done
+            [$callback] [$($callback_args)*]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [< $($depth)*] [$($inter)* <]
             [$($token)*]
         }
     };
     (
+        @done
         [$callback:path]
         [$($callback_args:tt)*]
-        [$($inter:tt)*]
-        [$token:tt $($other_tokens:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [$($depth:tt)*] [$($inter:tt)*]
+        [ << $($token:tt)*]
     ) => {
-        $crate::allow_where_clause_impl! {
+        $crate::parse_generics_impl! {
+            @done
             [$callback] [$($callback_args)*]
-            [$($inter)* $token]
-            [$($other_tokens)*]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [< < $($depth)*] [$($inter)* <<]
+            [$($token)*]
         }
     };
-}
-
-/// Concats several [`parse`](parse) calls results together.
-#[macro_export]
-macro_rules! concat {
     (
-        $callback:path { $($callback_args:tt)* }
-        $($([$($g:tt)*] [$($r:tt)*] [$($w:tt)*]),+ $(,)?)?
+        @done
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [< $($depth:tt)*] [$($inter:tt)*]
+        [ > $($token:tt)*]
     ) => {
-        $crate::concat_impl! {
+        $crate::parse_generics_impl! {
+            @done
             [$callback] [$($callback_args)*]
-            [$($([$($g)*])+)?] [$($([$($r)*])+)?] [$($([$($w)*])+)?]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [$($depth)*] [$($inter)* >]
+            [$($token)*]
         }
     };
-}
-
+    (
+        @done
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [< < $($depth:tt)*] [$($inter:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_generics_impl! {
+            @done
+            [$callback] [$($callback_args)*]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [$($depth)*] [$($inter)* >>]
+            [$($token)*]
+        }
+    };
+    (
+        @done
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [$($depth:tt)*] [$($inter:tt)*]
+        [$token:tt $($other_tokens:tt)*]
+    ) => {
+        $crate::parse_generics_impl! {
+            @done
+            [$callback] [$($callback_args)*]
+            [$($g)*]
+            [$($r)*]
+            [$($s)*]
+            [$($depth)*] [$($inter)* $token]
+            [$($other_tokens)*]
+        }
+    };
+    (
+        @done
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*]
+        [$($r:tt)*]
+        [$($s:tt)*]
+        [$($depth:tt)+] [$($inter:tt)*]
+        []
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_angle_bracket] [$($inter)*] [[$($g)*]] []
+            $crate::std_concat!(
+                "missing '>' after '",
+                $crate::std_stringify!($($inter)*),
+                "'"
+            )
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_where_clause_impl {
+    (
+        @done
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*] [$($r:tt)*] [$($s:tt)*] [$($d:tt)*] [$($a:tt)*] [$($f:tt)*]
+        [$($inter:tt)*]
+        [$($([[$($ty:tt)*] [$($bounds:tt)*]])+)?]
+        [$($relaxed:ident)*]
+        [$($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [
+                [$($g)*]
+                [$($r)*]
+                [$(where $($($ty)* : $($bounds)*),+)?]
+                [$($s)*]
+                [$($([[$($ty)*] [$($bounds)*]])+)?]
+                [$($d)*]
+                [$($a)*]
+                [$($f)*]
+                [$($relaxed)*]
+                $crate $crate
+            ]
+            $($inter)* $($token)*
+        }
+    };
+    (
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($g:tt)*] [$($r:tt)*] [$($s:tt)*] [$($d:tt)*] [$($a:tt)*] [$($f:tt)*]
+        [$($inter:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            [$crate::parse_where_clause_impl]
+            [@done [$callback] [$($callback_args)*] [$($g)*] [$($r)*] [$($s)*] [$($d)*] [$($a)*] [$($f)*] [$($inter)*]]
+            [$($token)*]
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! allow_where_clause_impl {
+    (
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($inter:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [$($callback_args)*]
+            [] [$($inter)*]
+            [$($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [] [$($inter:tt)*]
+        [ ; $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [
+                []
+                []
+                []
+                []
+                []
+                []
+                []
+                []
+                []
+                $crate $crate
+            ]
+            $($inter)* ; $($token)*
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [] [$($inter:tt)*]
+        [ $( { $($body:tt)* } $($token:tt)* )? ]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [
+                []
+                []
+                []
+                []
+                []
+                []
+                []
+                []
+                []
+                $crate $crate
+            ]
+            $($inter)* $( { $($body)* } $($token)* )?
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [] [$($inter:tt)*]
+        [where $($token:tt)*]
+    ) => {
+        $crate::parse_where_clause_impl! { 
+            [$callback]
+            [$($callback_args)*]
+            [] [] [] [] [] []
+            [$($inter)*]
+            [$($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [@until [[=] $($c:tt)*] $($callback_args:tt)*]
+        [] [$($inter:tt)*]
+        [ = $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [@until [[=] $($c)*] $($callback_args)*]
+            [] [$($inter)*]
+            [where = $($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [@until [$c0:tt [=>] $($c:tt)*] $($callback_args:tt)*]
+        [] [$($inter:tt)*]
+        [ => $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [@until [$c0 [=>] $($c)*] $($callback_args)*]
+            [] [$($inter)*]
+            [where => $($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [@until [$c0:tt $c1:tt [->] $($c:tt)*] $($callback_args:tt)*]
+        [] [$($inter:tt)*]
+        [ -> $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [@until [$c0 $c1 [->] $($c)*] $($callback_args)*]
+            [] [$($inter)*]
+            [where -> $($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [@until [$c0:tt $c1:tt $c2:tt [|] $($c:tt)*] $($callback_args:tt)*]
+        [] [$($inter:tt)*]
+        [ | $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [@until [$c0 $c1 $c2 [|] $($c)*] $($callback_args)*]
+            [] [$($inter)*]
+            [where | $($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [@until [$c0:tt $c1:tt $c2:tt $c3:tt [in] $($c:tt)*] $($callback_args:tt)*]
+        [] [$($inter:tt)*]
+        [ in $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [@until [$c0 $c1 $c2 $c3 [in] $($c)*] $($callback_args)*]
+            [] [$($inter)*]
+            [where in $($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [@until [$c0:tt $c1:tt $c2:tt $c3:tt $c4:tt [as] $($c:tt)*] $($callback_args:tt)*]
+        [] [$($inter:tt)*]
+        [ as $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [@until [$c0 $c1 $c2 $c3 $c4 [as] $($c)*] $($callback_args)*]
+            [] [$($inter)*]
+            [where as $($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($depth:tt)*] [$($inter:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [$($callback_args)*]
+            [< $($depth)*] [$($inter)* <]
+            [$($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($depth:tt)*] [$($inter:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [$($callback_args)*]
+            [< < $($depth)*] [$($inter)* <<]
+            [$($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [< $($depth:tt)*] [$($inter:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [$($callback_args)*]
+            [$($depth)*] [$($inter)* >]
+            [$($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [< < $($depth:tt)*] [$($inter:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [$($callback_args)*]
+            [$($depth)*] [$($inter)* >>]
+            [$($token)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($depth:tt)*] [$($inter:tt)*]
+        [$token:tt $($other_tokens:tt)*]
+    ) => {
+        $crate::allow_where_clause_impl! {
+            @inter
+            [$callback] [$($callback_args)*]
+            [$($depth)*] [$($inter)* $token]
+            [$($other_tokens)*]
+        }
+    };
+    (
+        @inter
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($depth:tt)+] [$($inter:tt)*]
+        []
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_angle_bracket] [$($inter)*] [] []
+            $crate::std_concat!(
+                "missing '>' after '",
+                $crate::std_stringify!($($inter)*),
+                "'"
+            )
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! where_predicates_impl {
+    (
+        [$callback:path]
+        [$($callback_args:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @ty
+            [$callback] [$($callback_args)*]
+            [] [] [] []
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [] []
+        [ , $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @ty
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [] []
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)+] []
+        [ : $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @bounds
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)+] [] []
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [@done [$done_callback:path] [@until [[=] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [] []
+        [ = $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [[=] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)*]
+            [$($u)*]
+            [ = $($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt [=>] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [] []
+        [ => $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 [=>] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)*]
+            [$($u)*]
+            [ => $($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt $c1:tt [->] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [] []
+        [ -> $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 $c1 [->] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)*]
+            [$($u)*]
+            [ -> $($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt $c1:tt $c2:tt [|] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [] []
+        [ | $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 $c1 $c2 [|] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)*]
+            [$($u)*]
+            [ | $($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt $c1:tt $c2:tt $c3:tt [in] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [] []
+        [ in $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 $c1 $c2 $c3 [in] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)*]
+            [$($u)*]
+            [ in $($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt $c1:tt $c2:tt $c3:tt $c4:tt [as] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [] []
+        [ as $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 $c1 $c2 $c3 $c4 [as] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)*]
+            [$($u)*]
+            [ as $($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [] []
+        [ ; $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($p)*]
+            [$($u)*]
+            [ ; $($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [] []
+        [ $( { $($body:tt)* } $($token:tt)* )? ]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($p)*]
+            [$($u)*]
+            [ $( { $($body)* } $($token)* )? ]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$([[$($pty:tt)*] [$($pb:tt)*]])*] [$($u:tt)*] [$($ty:tt)+] [$($depth:tt)+]
+        []
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_angle_bracket] [$($ty)+] [] [$($($pty)* : $($pb)* ,)*]
+            $crate::std_concat!(
+                "missing '>' after '",
+                $crate::std_stringify!($($ty)+),
+                "'"
+            )
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$([[$($pty:tt)*] [$($pb:tt)*]])*] [$($u:tt)*] [$($ty:tt)+] []
+        [ $(, $($token:tt)*)? ]
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_colon] [$($ty)+] [] [$($($pty)* : $($pb)* ,)* $($($token)*)?]
+            $crate::std_concat!(
+                "missing ':' after '",
+                $crate::std_stringify!($($ty)+),
+                "'"
+            )
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$([[$($pty:tt)*] [$($pb:tt)*]])*] [$($u:tt)*] [$($ty:tt)+] []
+        [ ; $($token:tt)*]
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_colon] [$($ty)+] [] [$($($pty)* : $($pb)* ,)* ; $($token)*]
+            $crate::std_concat!(
+                "missing ':' after '",
+                $crate::std_stringify!($($ty)+),
+                "'"
+            )
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$([[$($pty:tt)*] [$($pb:tt)*]])*] [$($u:tt)*] [$($ty:tt)+] []
+        [ { $($body:tt)* } $($token:tt)*]
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_colon] [$($ty)+] [] [$($($pty)* : $($pb)* ,)* { $($body)* } $($token)*]
+            $crate::std_concat!(
+                "missing ':' after '",
+                $crate::std_stringify!($($ty)+),
+                "'"
+            )
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @ty
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @ty
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @ty
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @ty
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @ty
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] []
+        [ , $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @ty
+            [$callback] [$($callback_args)*]
+            [$($p)* [[$($ty)*] [$($bounds)*]]] [$($u)*] [] []
+            [$($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [@done [$done_callback:path] [@until [[=] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] []
+        [ = $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [[=] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)* [[$($ty)*] [$($bounds)*]]]
+            [$($u)*]
+            [ = $($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt [=>] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] []
+        [ => $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 [=>] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)* [[$($ty)*] [$($bounds)*]]]
+            [$($u)*]
+            [ => $($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt $c1:tt [->] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] []
+        [ -> $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 $c1 [->] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)* [[$($ty)*] [$($bounds)*]]]
+            [$($u)*]
+            [ -> $($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt $c1:tt $c2:tt [|] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] []
+        [ | $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 $c1 $c2 [|] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)* [[$($ty)*] [$($bounds)*]]]
+            [$($u)*]
+            [ | $($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt $c1:tt $c2:tt $c3:tt [in] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] []
+        [ in $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 $c1 $c2 $c3 [in] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)* [[$($ty)*] [$($bounds)*]]]
+            [$($u)*]
+            [ in $($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [@done [$done_callback:path] [@until [$c0:tt $c1:tt $c2:tt $c3:tt $c4:tt [as] $($c:tt)*] $($until_args:tt)*] $($done_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] []
+        [ as $($token:tt)*]
+    ) => {
+        $callback ! {
+            @done [$done_callback] [@until [$c0 $c1 $c2 $c3 $c4 [as] $($c)*] $($until_args)*] $($done_args)*
+            [$($p)* [[$($ty)*] [$($bounds)*]]]
+            [$($u)*]
+            [ as $($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] []
+        [ ; $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($p)* [[$($ty)*] [$($bounds)*]]]
+            [$($u)*]
+            [ ; $($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$([[$($pty:tt)*] [$($pb:tt)*]])*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] [$($depth:tt)+]
+        []
+    ) => {
+        $crate::parse_error_impl! {
+            [$($callback_args)*] [missing_angle_bracket] [$($ty)*] [] [$($($pty)* : $($pb)* ,)*]
+            $crate::std_concat!(
+                "missing '>' after '",
+                $crate::std_stringify!($($ty)* : $($bounds)*),
+                "'"
+            )
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] []
+        [ $( { $($body:tt)* } $($token:tt)* )? ]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($p)* [[$($ty)*] [$($bounds)*]]]
+            [$($u)*]
+            [ $( { $($body)* } $($token)* )? ]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @bounds
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)*] [$($bounds)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @bounds
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)*] [$($bounds)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @bounds
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)*] [$($bounds)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @bounds
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)*] [$($bounds)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$ty:ident] [$($bounds:tt)*] []
+        [ ? $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @bounds
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)* $ty] [$ty] [$($bounds)* ?] []
+            [$($token)*]
+        }
+    };
+    (
+        @bounds
+        [$callback:path] [$($callback_args:tt)*]
+        [$($p:tt)*] [$($u:tt)*] [$($ty:tt)*] [$($bounds:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::where_predicates_impl! {
+            @bounds
+            [$callback] [$($callback_args)*]
+            [$($p)*] [$($u)*] [$($ty)*] [$($bounds)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+}
+
+/// Parses an item header: outer attributes, visibility, keyword, name, (optional) generics
+/// and (optional) where clause.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( #[$attr] )*
+/// $vis
+/// $( struct | enum | union | trait | type ) $name
+/// $(
+///     < $generics >
+///     $( $tokens_between_generics_and_where_clause )*
+///     $(
+///         where $where_clause
+///     )?
+/// )?
+/// $(
+///     $( ; | { $($body)* } )
+///     $($remaining_tokens)*
+/// )?
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( #[$attr] )* ]
+///     [ $vis ]
+///     [ $( struct | enum | union | trait | type ) ]
+///     [ $name ]
+///     [ $( < $generics > )? ]
+///     [ $( < $generics_without_constraints > )? ]
+///     [ $( where $where_clause )? ]
+///     $($( $tokens_between_generics_and_where_clause )*)?
+///     $(
+///         $( ; | { $($body)* } )
+///         $($remaining_tokens)*
+///     )?
+/// }
+/// ```
+///
+/// The generics and the where clause are handled exactly as in [`parse`](parse). For `type`,
+/// the where clause also ends at `=`, so `type Alias<T> where T: Copy = Vec<T>;` forwards
+/// `= Vec<T>;` unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// pub trait TheTrait { }
+///
+/// #[doc(hidden)]
+/// pub use generics2::parse_item as generics_parse_item;
+///
+/// #[macro_export]
+/// macro_rules! the_trait {
+///     (
+///         @impl [$($attr:tt)*] [$($vis:tt)*] [$keyword:tt] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] $($body:tt)*
+///     ) => {
+///         impl $($g)* $crate::TheTrait for $name $($r)* $($w)* { }
+///     };
+///     (
+///         $($token:tt)*
+///     ) => {
+///         $($token)*
+///
+///         $crate::generics_parse_item! {
+///             $crate::the_trait {
+///                 @impl
+///             }
+///             $($token)*
+///         }
+///     };
+/// }
+///
+/// the_trait! {
+///     #[derive(Clone)]
+///     pub(crate) struct Foo<'a, T: 'a> where T: Clone {
+///         t: &'a T,
+///     }
+/// }
+/// # fn main() { }
+/// ```
+#[macro_export]
+macro_rules! parse_item {
+    (
+        $callback:path { $($callback_args:tt)* } $($token:tt)*
+    ) => {
+        $crate::parse_item_impl! { @attrs [$callback] [$($callback_args)*] [] [$($token)*] }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_item_impl {
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [ # [$($attr:tt)*] $($token:tt)*]
+    ) => {
+        $crate::parse_item_impl! {
+            @attrs
+            [$callback] [$($callback_args)*]
+            [$($attrs)* # [$($attr)*]]
+            [$($token)*]
+        }
+    };
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [ pub ( $($restriction:tt)* ) $($token:tt)*]
+    ) => {
+        $crate::parse_item_impl! {
+            @keyword
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [pub ( $($restriction)* )]
+            [$($token)*]
+        }
+    };
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [ pub $($token:tt)*]
+    ) => {
+        $crate::parse_item_impl! {
+            @keyword
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [pub]
+            [$($token)*]
+        }
+    };
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_item_impl! {
+            @keyword
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] []
+            [$($token)*]
+        }
+    };
+    (
+        @keyword
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [ struct $name:ident $($token:tt)*]
+    ) => {
+        $crate::parse! {
+            $callback { $($callback_args)* [$($attrs)*] [$($vis)*] [struct] [$name] }
+            $($token)*
+        }
+    };
+    (
+        @keyword
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [ enum $name:ident $($token:tt)*]
+    ) => {
+        $crate::parse! {
+            $callback { $($callback_args)* [$($attrs)*] [$($vis)*] [enum] [$name] }
+            $($token)*
+        }
+    };
+    (
+        @keyword
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [ union $name:ident $($token:tt)*]
+    ) => {
+        $crate::parse! {
+            $callback { $($callback_args)* [$($attrs)*] [$($vis)*] [union] [$name] }
+            $($token)*
+        }
+    };
+    (
+        @keyword
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [ trait $name:ident $($token:tt)*]
+    ) => {
+        $crate::parse! {
+            $callback { $($callback_args)* [$($attrs)*] [$($vis)*] [trait] [$name] }
+            $($token)*
+        }
+    };
+    (
+        @keyword
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [ type $name:ident $($token:tt)*]
+    ) => {
+        $crate::parse! {
+            $callback { $($callback_args)* [$($attrs)*] [$($vis)*] [type] [$name] }
+            until [=] $($token)*
+        }
+    };
+    (
+        @keyword
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "', expected 'struct', 'enum', 'union', 'trait', or 'type'"
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @keyword
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!(
+            "missing 'struct', 'enum', 'union', 'trait', or 'type'"
+        );
+    };
+}
+
+/// Parses a struct declaration into its fields.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( #[$attr] )*
+/// $vis struct $name
+/// $( < $generics > )?
+/// $(
+///     ( $( $tuple_field ),* $(,)? )
+/// )?
+/// $(
+///     where $where_clause
+/// )?
+/// $( ; | { $( $named_field ),* $(,)? } )
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( #[$attr] )* ]
+///     [ $vis ]
+///     [ $name ]
+///     [ $( < $generics > )? ]
+///     [ $( < $generics_without_constraints > )? ]
+///     [ $( where $where_clause )? ]
+///     [ $kind ]
+///     [ $( [ $field_name [ $( #[$field_attr] )* ] [ $field_vis ] [ $field_type ] ] )* ]
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// where `$kind` is one of `unit`, `tuple` or `struct`, as in [`parse_enum`](parse_enum).
+/// Tuple struct fields are named by their position (`0`, `1`, ...). The where clause is
+/// reported in the same place whether it follows the generics of a braced struct or the
+/// fields of a tuple struct, and the closing `;` of unit and tuple structs is consumed.
+///
+/// # Examples
+///
+/// ```rust
+/// #[doc(hidden)]
+/// pub use generics2::parse_struct as generics_parse_struct;
+///
+/// pub trait FieldCount {
+///     const FIELD_COUNT: usize;
+/// }
+///
+/// #[macro_export]
+/// macro_rules! derive_field_count {
+///     (
+///         @struct [$($attr:tt)*] [$($vis:tt)*] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+///         [$kind:ident] [$([$field:tt $($field_info:tt)*])*]
+///     ) => {
+///         impl $($g)* $crate::FieldCount for $name $($r)* $($w)* {
+///             const FIELD_COUNT: usize = 0 $(+ { let _ = stringify!($field); 1 })*;
+///         }
+///     };
+///     (
+///         $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_struct! {
+///             $crate::derive_field_count {
+///                 @struct
+///             }
+///             $($token)*
+///         }
+///     };
+/// }
+///
+/// pub struct Pair<T>(T, T) where T: Copy;
+///
+/// derive_field_count! {
+///     pub struct Pair<T>(T, T) where T: Copy;
+/// }
+/// # fn main() { assert_eq!(<Pair<u8> as FieldCount>::FIELD_COUNT, 2); }
+/// ```
+#[macro_export]
+macro_rules! parse_struct {
+    (
+        $callback:path { $($callback_args:tt)* } $($token:tt)*
+    ) => {
+        $crate::parse_item! {
+            $crate::parse_struct_impl {
+                @item [$callback] [$($callback_args)*]
+            }
+            $($token)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_struct_impl {
+    (
+        @item [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [struct] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        { $($field:tt)* }
+        $($token:tt)*
+    ) => {
+        $crate::parse_fields_impl! {
+            @field
+            [$crate::parse_struct_impl] [
+                @fields
+                [$callback] [$($callback_args)*]
+                [[$($attrs)*] [$($vis)*] [$name] [$($g)*] [$($r)*] [$($w)*] [struct]]
+                [$($token)*]
+            ]
+            [] [named] []
+            []
+            [$($field)*]
+        }
+    };
+    (
+        @item [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [struct] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        ( $($field:tt)* ) ;
+        $($token:tt)*
+    ) => {
+        $crate::parse_fields_impl! {
+            @field
+            [$crate::parse_struct_impl] [
+                @fields
+                [$callback] [$($callback_args)*]
+                [[$($attrs)*] [$($vis)*] [$name] [$($g)*] [$($r)*] [$($w)*] [tuple]]
+                [$($token)*]
+            ]
+            [] [tuple] [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31]
+            []
+            [$($field)*]
+        }
+    };
+    (
+        @item [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [struct] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        ;
+        $($token:tt)*
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($attrs)*] [$($vis)*] [$name] [$($g)*] [$($r)*] [$($w)*] [unit]
+            []
+            $($token)*
+        }
+    };
+    (
+        @item [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [struct] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing struct body after '",
+            $crate::std_stringify!($name),
+            "'"
+        ));
+    };
+    (
+        @item [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$keyword:ident] $($token:tt)*
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($keyword),
+            "', expected 'struct'"
+        ));
+        $crate::error_at_impl! { [] $keyword }
+    };
+    (
+        @fields
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($token:tt)*]
+        [$($fields:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            $($header)*
+            [$($fields)*]
+            $($token)*
+        }
+    };
+}
+
+/// Parses an enum declaration into its variants.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( #[$attr] )*
+/// $vis enum $name
+/// $( < $generics > )?
+/// $(
+///     where $where_clause
+/// )?
+/// { $( $variant ),* $(,)? }
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( #[$attr] )* ]
+///     [ $vis ]
+///     [ $name ]
+///     [ $( < $generics > )? ]
+///     [ $( < $generics_without_constraints > )? ]
+///     [ $( where $where_clause )? ]
+///     [ $( [ $kind $variant_name [ $( #[$variant_attr] )* ] [ $($field)* ] [ $( $discriminant )? ] ] )* ]
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// where `$kind` is one of `unit`, `tuple` or `struct`, and each `$field` is
+///
+/// ```ignore
+/// [ $field_name [ $( #[$field_attr] )* ] [ $field_vis ] [ $field_type ] ]
+/// ```
+///
+/// Tuple variant fields are named by their position (`0`, `1`, ...), so they can be used
+/// directly in patterns like `Self::$variant_name { $( $field_name: _ ),* }`.
+///
+/// # Examples
+///
+/// ```rust
+/// #[doc(hidden)]
+/// pub use generics2::parse_enum as generics_parse_enum;
+///
+/// pub trait VariantName {
+///     fn variant_name(&self) -> &'static str;
+/// }
+///
+/// #[macro_export]
+/// macro_rules! derive_variant_name {
+///     (
+///         @enum [$($attr:tt)*] [$($vis:tt)*] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+///         [$([$kind:ident $variant:ident [$($variant_attr:tt)*] [$([$field:tt $($field_info:tt)*])*] [$($discriminant:tt)*]])*]
+///     ) => {
+///         impl $($g)* $crate::VariantName for $name $($r)* $($w)* {
+///             fn variant_name(&self) -> &'static str {
+///                 match self {
+///                     $(Self::$variant { $($field: _),* } => stringify!($variant)),*
+///                 }
+///             }
+///         }
+///     };
+///     (
+///         $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_enum! {
+///             $crate::derive_variant_name {
+///                 @enum
+///             }
+///             $($token)*
+///         }
+///     };
+/// }
+///
+/// pub enum Shape<T> { Empty, Point(T, T), Circle { radius: T } }
+///
+/// derive_variant_name! {
+///     pub enum Shape<T> { Empty, Point(T, T), Circle { radius: T } }
+/// }
+/// # fn main() { assert_eq!(Shape::Point(1, 2).variant_name(), "Point"); }
+/// ```
+#[macro_export]
+macro_rules! parse_enum {
+    (
+        $callback:path { $($callback_args:tt)* } $($token:tt)*
+    ) => {
+        $crate::parse_item! {
+            $crate::parse_enum_impl {
+                @item [$callback] [$($callback_args)*]
+            }
+            $($token)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_enum_impl {
+    (
+        @item [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [enum] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        { $($body:tt)* }
+        $($token:tt)*
+    ) => {
+        $crate::parse_enum_impl! {
+            @variant
+            [$callback] [$($callback_args)*]
+            [[$($attrs)*] [$($vis)*] [$name] [$($g)*] [$($r)*] [$($w)*]]
+            []
+            [$($token)*]
+            []
+            [$($body)*]
+        }
+    };
+    (
+        @item [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [enum] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing enum body after '",
+            $crate::std_stringify!($name),
+            "'"
+        ));
+    };
+    (
+        @item [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$keyword:ident] $($token:tt)*
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($keyword),
+            "', expected 'enum'"
+        ));
+        $crate::error_at_impl! { [] $keyword }
+    };
+    (
+        @variant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($attrs:tt)*]
+        [ # [$($attr:tt)*] $($token:tt)*]
+    ) => {
+        $crate::parse_enum_impl! {
+            @variant
+            [$callback] [$($callback_args)*] [$($header)*] [$($variants)*]
+            [$($rest)*]
+            [$($attrs)* # [$($attr)*]]
+            [$($token)*]
+        }
+    };
+    (
+        @variant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($attrs:tt)*]
+        [ $name:ident ( $($field:tt)* ) $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @field
+            [$crate::parse_enum_impl] [
+                @discriminant
+                [$callback] [$($callback_args)*] [$($header)*] [$($variants)*]
+                [$($rest)*]
+                [tuple $name [$($attrs)*]]
+                [$($token)*]
+            ]
+            [] [tuple] [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31]
+            []
+            [$($field)*]
+        }
+    };
+    (
+        @variant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($attrs:tt)*]
+        [ $name:ident { $($field:tt)* } $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @field
+            [$crate::parse_enum_impl] [
+                @discriminant
+                [$callback] [$($callback_args)*] [$($header)*] [$($variants)*]
+                [$($rest)*]
+                [struct $name [$($attrs)*]]
+                [$($token)*]
+            ]
+            [] [named] []
+            []
+            [$($field)*]
+        }
+    };
+    (
+        @variant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($attrs:tt)*]
+        [ $name:ident $($token:tt)*]
+    ) => {
+        $crate::parse_enum_impl! {
+            @discriminant
+            [$callback] [$($callback_args)*] [$($header)*] [$($variants)*]
+            [$($rest)*]
+            [unit $name [$($attrs)*]]
+            [$($token)*]
+            []
+        }
+    };
+    (
+        @variant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        []
+        []
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            $($header)*
+            [$($variants)*]
+            $($rest)*
+        }
+    };
+    (
+        @variant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($attrs:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "', expected variant name"
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @variant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($attrs:tt)+]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing variant after '",
+            $crate::std_stringify!($($attrs)+),
+            "'"
+        ));
+    };
+    (
+        @discriminant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($variant:tt)*]
+        [ = $($token:tt)*]
+        [$($fields:tt)*]
+    ) => {
+        $crate::parse_enum_impl! {
+            @value
+            [$callback] [$($callback_args)*] [$($header)*] [$($variants)*]
+            [$($rest)*]
+            [$($variant)* [$($fields)*]]
+            []
+            [$($token)*]
+        }
+    };
+    (
+        @discriminant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($variant:tt)*]
+        [ $(, $($token:tt)*)? ]
+        [$($fields:tt)*]
+    ) => {
+        $crate::parse_enum_impl! {
+            @variant
+            [$callback] [$($callback_args)*] [$($header)*] [$($variants)* [$($variant)* [$($fields)*] []]]
+            [$($rest)*]
+            []
+            [$($($token)*)?]
+        }
+    };
+    (
+        @discriminant
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$kind:ident $name:ident $($variant:tt)*]
+        [$x:tt $($token:tt)*]
+        [$($fields:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "' after variant '",
+            $crate::std_stringify!($name),
+            "', expected '=' or ','"
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @value
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($variant:tt)*]
+        [$($value:tt)+]
+        [ $(, $($token:tt)*)? ]
+    ) => {
+        $crate::parse_enum_impl! {
+            @variant
+            [$callback] [$($callback_args)*] [$($header)*] [$($variants)* [$($variant)* [$($value)+]]]
+            [$($rest)*]
+            []
+            [$($($token)*)?]
+        }
+    };
+    (
+        @value
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$kind:ident $name:ident $($variant:tt)*]
+        []
+        [ $(, $($token:tt)*)? ]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing discriminant after '",
+            $crate::std_stringify!($name =),
+            "'"
+        ));
+    };
+    (
+        @value
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($variants:tt)*]
+        [$($rest:tt)*]
+        [$($variant:tt)*]
+        [$($value:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::parse_enum_impl! {
+            @value
+            [$callback] [$($callback_args)*] [$($header)*] [$($variants)*]
+            [$($rest)*]
+            [$($variant)*]
+            [$($value)* $x]
+            [$($token)*]
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_fields_impl {
+    (
+        @field
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*]
+        [ # [$($attr:tt)*] $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @field
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$($attrs)* # [$($attr)*]]
+            [$($token)*]
+        }
+    };
+    (
+        @field
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        []
+        []
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($fields)*]
+        }
+    };
+    (
+        @field
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @vis
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$($attrs)*]
+            [$($token)*]
+        }
+    };
+    (
+        @vis
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*]
+        [ pub ( crate ) $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @name
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$($attrs)*] [pub ( crate )]
+            [$($token)*]
+        }
+    };
+    (
+        @vis
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*]
+        [ pub ( self ) $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @name
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$($attrs)*] [pub ( self )]
+            [$($token)*]
+        }
+    };
+    (
+        @vis
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*]
+        [ pub ( super ) $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @name
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$($attrs)*] [pub ( super )]
+            [$($token)*]
+        }
+    };
+    (
+        @vis
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*]
+        [ pub ( in $($path:tt)* ) $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @name
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$($attrs)*] [pub ( in $($path)* )]
+            [$($token)*]
+        }
+    };
+    (
+        @vis
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*]
+        [ pub $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @name
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$($attrs)*] [pub]
+            [$($token)*]
+        }
+    };
+    (
+        @vis
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*]
+        [  $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @name
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$($attrs)*] []
+            [$($token)*]
+        }
+    };
+    (
+        @name
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [ $name:ident : $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @ty
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$name [$($attrs)*] [$($vis)*]]
+            [] []
+            [$($token)*]
+        }
+    };
+    (
+        @name
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [tuple] [$index:tt $($indices:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [$($token:tt)+]
+    ) => {
+        $crate::parse_fields_impl! {
+            @ty
+            [$callback] [$($callback_args)*] [$($fields)*] [tuple] [$($indices)*]
+            [$index [$($attrs)*] [$($vis)*]]
+            [] []
+            [$($token)+]
+        }
+    };
+    (
+        @name
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [tuple] []
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [$($token:tt)+]
+    ) => {
+        $crate::std_compile_error!("too many tuple fields");
+    };
+    (
+        @name
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "', expected field name"
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @name
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing field after '",
+            $crate::std_stringify!($($attrs)* $($vis)*),
+            "'"
+        ));
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$name:tt [$($attrs:tt)*] [$($vis:tt)*]]
+        [$($ty:tt)+] []
+        [ $(, $($token:tt)*)? ]
+    ) => {
+        $crate::parse_fields_impl! {
+            @field
+            [$callback] [$($callback_args)*] [$($fields)* [$name [$($attrs)*] [$($vis)*] [$($ty)+]]] [$mode] [$($index)*]
+            []
+            [$($($token)*)?]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$name:tt [$($attrs:tt)*] [$($vis:tt)*]]
+        [] []
+        [ $(, $($token:tt)*)? ]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing type for field '",
+            $crate::std_stringify!($name),
+            "'"
+        ));
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$name:tt [$($attrs:tt)*] [$($vis:tt)*]]
+        [$($ty:tt)*] [$($depth:tt)+]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing '>' after '",
+            $crate::std_stringify!($($ty)*),
+            "'"
+        ));
+        $crate::error_at_impl! { [] $($ty)* }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$name:tt [$($attrs:tt)*] [$($vis:tt)*]]
+        [$($ty:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @ty
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$name [$($attrs)*] [$($vis)*]]
+            [$($ty)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$name:tt [$($attrs:tt)*] [$($vis:tt)*]]
+        [$($ty:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @ty
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$name [$($attrs)*] [$($vis)*]]
+            [$($ty)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$name:tt [$($attrs:tt)*] [$($vis:tt)*]]
+        [$($ty:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @ty
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$name [$($attrs)*] [$($vis)*]]
+            [$($ty)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$name:tt [$($attrs:tt)*] [$($vis:tt)*]]
+        [$($ty:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @ty
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$name [$($attrs)*] [$($vis)*]]
+            [$($ty)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ty
+        [$callback:path] [$($callback_args:tt)*] [$($fields:tt)*] [$mode:ident] [$($index:tt)*]
+        [$name:tt [$($attrs:tt)*] [$($vis:tt)*]]
+        [$($ty:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::parse_fields_impl! {
+            @ty
+            [$callback] [$($callback_args)*] [$($fields)*] [$mode] [$($index)*]
+            [$name [$($attrs)*] [$($vis)*]]
+            [$($ty)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+}
+
+/// Parses a trait declaration header.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( #[$attr] )*
+/// $vis
+/// $( unsafe )? $( auto )? trait $name
+/// $( < $generics > )?
+/// $( : $( $supertrait )+* )?
+/// $(
+///     where $where_clause
+/// )?
+/// { $($body)* }
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( #[$attr] )* ]
+///     [ $vis ]
+///     [ $( unsafe )? ]
+///     [ $( auto )? ]
+///     [ $name ]
+///     [ $( < $generics > )? ]
+///     [ $( < $generics_without_constraints > )? ]
+///     [ $( where $where_clause )? ]
+///     [ $( [ $supertrait ] )* ]
+///     [ < $( $generics, )? __Self: $name $( < $generics_without_constraints > )? > ]
+///     { $($body)* }
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// Supertraits are separated on `+` outside of angle brackets. The last bracket holds
+/// generics for a blanket impl: the trait generics followed by a fresh `__Self` type
+/// parameter bounded by the trait itself.
+///
+/// # Examples
+///
+/// ```rust
+/// #[doc(hidden)]
+/// pub use generics2::parse_trait as generics_parse_trait;
+///
+/// #[macro_export]
+/// macro_rules! blanket_ref {
+///     (
+///         @trait [$($attr:tt)*] [$($vis:tt)*] [] [] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+///         [$([$($supertrait:tt)*])*] [$($blanket:tt)*] { $($body:tt)* }
+///     ) => {
+///         $($attr)* $($vis)* trait $name $($g)* : $($($supertrait)* +)* $($w)* { $($body)* }
+///
+///         impl $($blanket)* $name $($r)* for &__Self $($w)* { }
+///     };
+///     (
+///         $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_trait! {
+///             $crate::blanket_ref {
+///                 @trait
+///             }
+///             $($token)*
+///         }
+///     };
+/// }
+///
+/// blanket_ref! {
+///     pub trait Marker<T>: core::fmt::Debug where T: Clone { }
+/// }
+/// # fn main() { }
+/// ```
+#[macro_export]
+macro_rules! parse_trait {
+    (
+        $callback:path { $($callback_args:tt)* } $($token:tt)*
+    ) => {
+        $crate::parse_trait_impl! { @attrs [$callback] [$($callback_args)*] [] [$($token)*] }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_trait_impl {
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [ # [$($attr:tt)*] $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @attrs
+            [$callback] [$($callback_args)*]
+            [$($attrs)* # [$($attr)*]]
+            [$($token)*]
+        }
+    };
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [ pub ( $($restriction:tt)* ) $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @unsafe
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [pub ( $($restriction)* )]
+            [$($token)*]
+        }
+    };
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [ pub $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @unsafe
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [pub]
+            [$($token)*]
+        }
+    };
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @unsafe
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] []
+            [$($token)*]
+        }
+    };
+    (
+        @unsafe
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [ unsafe $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @auto
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [$($vis)*] [unsafe]
+            [$($token)*]
+        }
+    };
+    (
+        @unsafe
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @auto
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [$($vis)*] []
+            [$($token)*]
+        }
+    };
+    (
+        @auto
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($unsafety:tt)*]
+        [ auto trait $name:ident $($token:tt)*]
+    ) => {
+        $crate::parse_raw! {
+            $crate::parse_trait_impl {
+                @parsed
+                [$callback] [$($callback_args)*]
+                [[$($attrs)*] [$($vis)*] [$($unsafety)*] [auto] [$name]]
+                $name
+            }
+            $($token)*
+        }
+    };
+    (
+        @auto
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($unsafety:tt)*]
+        [ trait $name:ident $($token:tt)*]
+    ) => {
+        $crate::parse_raw! {
+            $crate::parse_trait_impl {
+                @parsed
+                [$callback] [$($callback_args)*]
+                [[$($attrs)*] [$($vis)*] [$($unsafety)*] [] [$name]]
+                $name
+            }
+            $($token)*
+        }
+    };
+    (
+        @auto
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($unsafety:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "', expected 'unsafe', 'auto', or 'trait'"
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @auto
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($unsafety:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!("missing 'trait'");
+    };
+    (
+        @parsed
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        $name:ident
+        [
+            [$([$([$($g:tt)*])*])?]
+            [$([$([$($r:tt)*])*])?]
+            [$($w:tt)*]
+            $($extra:tt)*
+        ]
+        $($token:tt)*
+    ) => {
+        $crate::parse_trait_impl! {
+            @supertraits
+            [$callback] [$($callback_args)*]
+            [
+                $($header)*
+                [ $(< $($($g)*),+ >)? ]
+                [ $(< $($($r)*),+ >)? ]
+                [$($w)*]
+            ]
+            [ < $($($($g)*),+ ,)? __Self: $name $(< $($($r)*),+ >)? > ]
+            []
+            [$($token)*]
+        }
+    };
+    (
+        @supertraits
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        []
+        [ : $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @supertrait
+            [$callback] [$($callback_args)*] [$($header)*] [$($blanket)*]
+            []
+            [] []
+            [$($token)*]
+        }
+    };
+    (
+        @supertraits
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        []
+        [ { $($body:tt)* } $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            $($header)*
+            []
+            [$($blanket)*]
+            { $($body)* } $($token)*
+        }
+    };
+    (
+        @supertraits
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        []
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "', expected ':' or trait body"
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @supertraits
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        []
+        []
+    ) => {
+        $crate::std_compile_error!("missing trait body");
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [] []
+        [ + $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @supertrait
+            [$callback] [$($callback_args)*] [$($header)*] [$($blanket)*]
+            [$($supertraits)*]
+            [] []
+            [$($token)*]
+        }
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [$($supertrait:tt)+] []
+        [ + $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @supertrait
+            [$callback] [$($callback_args)*] [$($header)*] [$($blanket)*]
+            [$($supertraits)* [$($supertrait)+]]
+            [] []
+            [$($token)*]
+        }
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [] []
+        [ { $($body:tt)* } $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            $($header)*
+            [$($supertraits)*]
+            [$($blanket)*]
+            { $($body)* } $($token)*
+        }
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [$($supertrait:tt)+] []
+        [ { $($body:tt)* } $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            $($header)*
+            [$($supertraits)* [$($supertrait)+]]
+            [$($blanket)*]
+            { $($body)* } $($token)*
+        }
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [$($supertrait:tt)*] [$($depth:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing trait body after '",
+            $crate::std_stringify!($($supertrait)*),
+            "'"
+        ));
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [$($supertrait:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @supertrait
+            [$callback] [$($callback_args)*] [$($header)*] [$($blanket)*]
+            [$($supertraits)*]
+            [$($supertrait)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [$($supertrait:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @supertrait
+            [$callback] [$($callback_args)*] [$($header)*] [$($blanket)*]
+            [$($supertraits)*]
+            [$($supertrait)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [$($supertrait:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @supertrait
+            [$callback] [$($callback_args)*] [$($header)*] [$($blanket)*]
+            [$($supertraits)*]
+            [$($supertrait)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [$($supertrait:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @supertrait
+            [$callback] [$($callback_args)*] [$($header)*] [$($blanket)*]
+            [$($supertraits)*]
+            [$($supertrait)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @supertrait
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($blanket:tt)*]
+        [$($supertraits:tt)*]
+        [$($supertrait:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::parse_trait_impl! {
+            @supertrait
+            [$callback] [$($callback_args)*] [$($header)*] [$($blanket)*]
+            [$($supertraits)*]
+            [$($supertrait)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+}
+
+/// Parses an `impl` block header.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( unsafe )? impl
+/// $( < $generics > )?
+/// $(
+///     $( ! )? $trait_path for
+/// )?
+/// $self_type
+/// $(
+///     where $where_clause
+/// )?
+/// { $($body)* }
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( unsafe )? ]
+///     [ $( < $generics > )? ]
+///     [ $( < $generics_without_constraints > )? ]
+///     [ $( where $where_clause )? ]
+///     [ $( ! )? ]
+///     [ $( $trait_path )? ]
+///     [ $self_type ]
+///     { $($body)* }
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// The generics and the where clause are handled exactly as in [`parse`](parse).
+/// The trait path (with its generic arguments) is separated from the self type
+/// at the first `for` outside of angle brackets; inherent impls get an empty trait path.
+///
+/// # Examples
+///
+/// ```rust
+/// pub trait TheTrait { }
+///
+/// #[doc(hidden)]
+/// pub use generics2::parse_impl as generics_parse_impl;
+///
+/// #[macro_export]
+/// macro_rules! also_the_trait {
+///     (
+///         @impl [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [] [$($trait_:tt)+] [$($self_ty:tt)*] { $($body:tt)* }
+///     ) => {
+///         $($unsafety)* impl $($g)* $($trait_)+ for $($self_ty)* $($w)* { $($body)* }
+///
+///         impl $($g)* $crate::TheTrait for $($self_ty)* $($w)* { }
+///     };
+///     (
+///         $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_impl! {
+///             $crate::also_the_trait {
+///                 @impl
+///             }
+///             $($token)*
+///         }
+///     };
+/// }
+///
+/// pub struct Foo<T>(T);
+///
+/// also_the_trait! {
+///     impl<T: Clone> Clone for Foo<T> where T: Copy {
+///         fn clone(&self) -> Self { Foo(self.0) }
+///     }
+/// }
+/// # fn main() { }
+/// ```
+#[macro_export]
+macro_rules! parse_impl {
+    (
+        $callback:path { $($callback_args:tt)* } unsafe impl $($token:tt)*
+    ) => {
+        $crate::parse! {
+            $crate::parse_impl_impl { @parsed [$callback] [$($callback_args)*] [unsafe] }
+            $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* } impl $($token:tt)*
+    ) => {
+        $crate::parse! {
+            $crate::parse_impl_impl { @parsed [$callback] [$($callback_args)*] [] }
+            $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* } $($token:tt)*
+    ) => {
+        $crate::std_compile_error!("missing 'impl'");
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_impl_impl {
+    (
+        @parsed
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        ! $($token:tt)*
+    ) => {
+        $crate::parse_impl_impl! {
+            @trait
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [!] [] []
+            [$($token)*]
+        }
+    };
+    (
+        @parsed
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::parse_impl_impl! {
+            @trait
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [] [] []
+            [$($token)*]
+        }
+    };
+    (
+        @trait
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*]
+        [$($trait_:tt)+] []
+        [ for $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @self
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*] [$($trait_)+]
+            [] []
+            [$($token)*]
+        }
+    };
+    (
+        @trait
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [] [$($self_ty:tt)+] []
+        [ { $($body:tt)* } $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [] [] [$($self_ty)+]
+            { $($body)* } $($token)*
+        }
+    };
+    (
+        @trait
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*]
+        [$($trait_:tt)*] []
+        [ { $($body:tt)* } $($token:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing 'for' after '",
+            $crate::std_stringify!($($negative)* $($trait_)*),
+            "'"
+        ));
+    };
+    (
+        @trait
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*]
+        [$($trait_:tt)*] [$($depth:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing impl body after '",
+            $crate::std_stringify!($($negative)* $($trait_)*),
+            "'"
+        ));
+    };
+    (
+        @trait
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*]
+        [$($trait_:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @trait
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*]
+            [$($trait_)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @trait
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*]
+        [$($trait_:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @trait
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*]
+            [$($trait_)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @trait
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*]
+        [$($trait_:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @trait
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*]
+            [$($trait_)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @trait
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*]
+        [$($trait_:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @trait
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*]
+            [$($trait_)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @trait
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*]
+        [$($trait_:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @trait
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*]
+            [$($trait_)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @self
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*] [$($trait_:tt)*]
+        [$($self_ty:tt)+] []
+        [ { $($body:tt)* } $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*] [$($trait_)*] [$($self_ty)+]
+            { $($body)* } $($token)*
+        }
+    };
+    (
+        @self
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*] [$($trait_:tt)*]
+        [$($self_ty:tt)*] [$($depth:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing impl body after '",
+            $crate::std_stringify!($($negative)* $($trait_)* for $($self_ty)*),
+            "'"
+        ));
+    };
+    (
+        @self
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*] [$($trait_:tt)*]
+        [$($self_ty:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @self
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*] [$($trait_)*]
+            [$($self_ty)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @self
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*] [$($trait_:tt)*]
+        [$($self_ty:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @self
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*] [$($trait_)*]
+            [$($self_ty)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @self
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*] [$($trait_:tt)*]
+        [$($self_ty:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @self
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*] [$($trait_)*]
+            [$($self_ty)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @self
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*] [$($trait_:tt)*]
+        [$($self_ty:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @self
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*] [$($trait_)*]
+            [$($self_ty)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @self
+        [$callback:path] [$($callback_args:tt)*]
+        [$($unsafety:tt)*] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        [$($negative:tt)*] [$($trait_:tt)*]
+        [$($self_ty:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::parse_impl_impl! {
+            @self
+            [$callback] [$($callback_args)*]
+            [$($unsafety)*] [$($g)*] [$($r)*] [$($w)*]
+            [$($negative)*] [$($trait_)*]
+            [$($self_ty)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+}
+
+/// Parses a function signature.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( #[$attr] )*
+/// $vis
+/// $( const )? $( async )? $( unsafe )? $( extern $( $abi )? )?
+/// fn $name
+/// $( < $generics > )?
+/// ( $( $arg_pattern $( : $arg_type )? ),* $(,)? )
+/// $( -> $return_type )?
+/// $(
+///     where $where_clause
+/// )?
+/// $( ; | { $($body)* } )
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( #[$attr] )* ]
+///     [ $vis ]
+///     [ $( const )? $( async )? $( unsafe )? $( extern $( $abi )? )? ]
+///     [ $name ]
+///     [ $( < $generics > )? ]
+///     [ $( < $generics_without_constraints > )? ]
+///     [ $( where $where_clause )? ]
+///     [ $([ [ $arg_pattern ] [ $( $arg_type )? ] ])* ]
+///     [ $( $return_type )? ]
+///     $( ; | { $($body)* } )
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// The generics and the where clause are handled exactly as in [`parse`](parse).
+/// Arguments are separated on commas outside of angle brackets, and `self` receivers
+/// without an explicit type get an empty `$arg_type`. The return type ends at the first
+/// `;` or `{ ... }` outside of angle brackets.
+///
+/// # Examples
+///
+/// ```rust
+/// #[doc(hidden)]
+/// pub use generics2::parse_fn as generics_parse_fn;
+///
+/// #[macro_export]
+/// macro_rules! traced {
+///     (
+///         @fn [$($attr:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+///         [$([[$arg:ident] [$($ty:tt)*]])*] [$ret:ty] { $($body:tt)* }
+///     ) => {
+///         $($attr)* $($vis)* $($qualifiers)* fn $name $($g)* ($($arg: $($ty)*),*) -> $ret $($w)* {
+///             let result = { $($body)* };
+///             result
+///         }
+///     };
+///     (
+///         $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_fn! {
+///             $crate::traced {
+///                 @fn
+///             }
+///             $($token)*
+///         }
+///     };
+/// }
+///
+/// traced! {
+///     pub fn first<T: Clone>(items: &[T], default: T) -> Option<T> where T: Default {
+///         items.first().cloned().or(Some(default))
+///     }
+/// }
+/// # fn main() { }
+/// ```
+#[macro_export]
+macro_rules! parse_fn {
+    (
+        $callback:path { $($callback_args:tt)* } $($token:tt)*
+    ) => {
+        $crate::parse_fn_impl! { @attrs [$callback] [$($callback_args)*] [] [$($token)*] }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_fn_impl {
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [ # [$($attr:tt)*] $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @attrs
+            [$callback] [$($callback_args)*]
+            [$($attrs)* # [$($attr)*]]
+            [$($token)*]
+        }
+    };
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [ pub ( $($restriction:tt)* ) $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @qualifiers
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [pub ( $($restriction)* )] []
+            [$($token)*]
+        }
+    };
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [ pub $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @qualifiers
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [pub] []
+            [$($token)*]
+        }
+    };
+    (
+        @attrs
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @qualifiers
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [] []
+            [$($token)*]
+        }
+    };
+    (
+        @qualifiers
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        [ fn $name:ident $($token:tt)*]
+    ) => {
+        $crate::parse! {
+            $crate::parse_fn_impl {
+                @signature
+                [$callback] [$($callback_args)*]
+                [[$($attrs)*] [$($vis)*] [$($qualifiers)*] [$name]]
+            }
+            $($token)*
+        }
+    };
+    (
+        @qualifiers
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        [ extern $abi:literal $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @qualifiers
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [$($vis)*] [$($qualifiers)* extern $abi]
+            [$($token)*]
+        }
+    };
+    (
+        @qualifiers
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        [ $qualifier:ident $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @qualifier $qualifier
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [$($vis)*] [$($qualifiers)*]
+            [$($token)*]
+        }
+    };
+    (
+        @qualifiers
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "', expected 'const', 'async', 'unsafe', 'extern', or 'fn'"
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @qualifiers
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!("missing 'fn'");
+    };
+    (
+        @qualifier const
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @qualifiers
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [$($vis)*] [$($qualifiers)* const]
+            [$($token)*]
+        }
+    };
+    (
+        @qualifier async
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @qualifiers
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [$($vis)*] [$($qualifiers)* async]
+            [$($token)*]
+        }
+    };
+    (
+        @qualifier unsafe
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @qualifiers
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [$($vis)*] [$($qualifiers)* unsafe]
+            [$($token)*]
+        }
+    };
+    (
+        @qualifier extern
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @qualifiers
+            [$callback] [$($callback_args)*]
+            [$($attrs)*] [$($vis)*] [$($qualifiers)* extern]
+            [$($token)*]
+        }
+    };
+    (
+        @qualifier $x:ident
+        [$callback:path] [$($callback_args:tt)*]
+        [$($attrs:tt)*] [$($vis:tt)*] [$($qualifiers:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "', expected 'const', 'async', 'unsafe', 'extern', or 'fn'"
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @signature
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        ( $($arg:tt)* ) $($token:tt)*
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($header)* [$($g)*] [$($r)*] [$($w)*]] [$($token)*]
+            []
+            [] []
+            [$($arg)*]
+        }
+    };
+    (
+        @signature
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing argument list before '",
+            $crate::std_stringify!($($token)*),
+            "'"
+        ));
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*]
+        [$($pat:tt)+] []
+        [ , $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)* [[$($pat)+] []]]
+            [] []
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*]
+        [$($pat:tt)+] []
+        []
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)* [[$($pat)+] []]]
+            [] []
+            []
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*]
+        [$($pat:tt)+] []
+        [ : $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg_type
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*] [$($pat)+]
+            [] []
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*]
+        [$($pat:tt)+] [$($depth:tt)+]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing '>' after '",
+            $crate::std_stringify!($($pat)+),
+            "'"
+        ));
+        $crate::error_at_impl! { [] $($pat)+ }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*]
+        [$($pat:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*]
+            [$($pat)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*]
+        [$($pat:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*]
+            [$($pat)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*]
+        [$($pat:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*]
+            [$($pat)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*]
+        [$($pat:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*]
+            [$($pat)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*]
+        [$($pat:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*]
+            [$($pat)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg_type
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*] [$($pat:tt)*]
+        [$($ty:tt)*] []
+        [ $(, $($token:tt)*)? ]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)* [[$($pat)*] [$($ty)*]]]
+            [] []
+            [$($($token)*)?]
+        }
+    };
+    (
+        @arg_type
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*] [$($pat:tt)*]
+        [$($ty:tt)*] [$($depth:tt)+]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing '>' after '",
+            $crate::std_stringify!($($pat)* : $($ty)*),
+            "'"
+        ));
+        $crate::error_at_impl! { [] $($pat)* $($ty)* }
+    };
+    (
+        @arg_type
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*] [$($pat:tt)*]
+        [$($ty:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg_type
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*] [$($pat)*]
+            [$($ty)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg_type
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*] [$($pat:tt)*]
+        [$($ty:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg_type
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*] [$($pat)*]
+            [$($ty)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg_type
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*] [$($pat:tt)*]
+        [$($ty:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg_type
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*] [$($pat)*]
+            [$($ty)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg_type
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*] [$($pat:tt)*]
+        [$($ty:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg_type
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*] [$($pat)*]
+            [$($ty)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg_type
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($rest:tt)*]
+        [$($args:tt)*] [$($pat:tt)*]
+        [$($ty:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @arg_type
+            [$callback] [$($callback_args)*] [$($header)*] [$($rest)*]
+            [$($args)*] [$($pat)*]
+            [$($ty)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [ -> $($token:tt)*]
+        [$($args:tt)*]
+        [] []
+        []
+    ) => {
+        $crate::parse_fn_impl! {
+            @ret
+            [$callback] [$($callback_args)*] [$($header)*]
+            [$($args)*]
+            [] []
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*] [$($token:tt)*]
+        [$($args:tt)*]
+        [] []
+        []
+    ) => {
+        $crate::parse_fn_impl! {
+            @ret
+            [$callback] [$($callback_args)*] [$($header)*]
+            [$($args)*]
+            [] []
+            [$($token)*]
+        }
+    };
+    (
+        @ret
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($args:tt)*]
+        [$($ret:tt)*] []
+        [ ; $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            $($header)*
+            [$($args)*]
+            [$($ret)*]
+            ; $($token)*
+        }
+    };
+    (
+        @ret
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($args:tt)*]
+        [$($ret:tt)*] []
+        [ { $($body:tt)* } $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            $($header)*
+            [$($args)*]
+            [$($ret)*]
+            { $($body)* } $($token)*
+        }
+    };
+    (
+        @ret
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($args:tt)*]
+        [$($ret:tt)*] [$($depth:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing function body after '",
+            $crate::std_stringify!($(-> $ret)*),
+            "'"
+        ));
+    };
+    (
+        @ret
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($args:tt)*]
+        [$($ret:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @ret
+            [$callback] [$($callback_args)*] [$($header)*]
+            [$($args)*]
+            [$($ret)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ret
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($args:tt)*]
+        [$($ret:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @ret
+            [$callback] [$($callback_args)*] [$($header)*]
+            [$($args)*]
+            [$($ret)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ret
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($args:tt)*]
+        [$($ret:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @ret
+            [$callback] [$($callback_args)*] [$($header)*]
+            [$($args)*]
+            [$($ret)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ret
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($args:tt)*]
+        [$($ret:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @ret
+            [$callback] [$($callback_args)*] [$($header)*]
+            [$($args)*]
+            [$($ret)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @ret
+        [$callback:path] [$($callback_args:tt)*] [$($header:tt)*]
+        [$($args:tt)*]
+        [$($ret:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::parse_fn_impl! {
+            @ret
+            [$callback] [$($callback_args)*] [$($header)*]
+            [$($args)*]
+            [$($ret)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+}
+
+/// Parses (optional) generic arguments at a use site, like the `<'a, T, 4, Item = X>` of a path.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( < $generic_arguments > )?
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( [ $kind $( $name )? [ $($argument)* ] ] )* ]
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// Each argument is categorised as one of
+///
+/// ```ignore
+/// [ lifetime [ 'a ] ]
+/// [ type [ Vec<T> ] ]
+/// [ const [ { N + 1 } ] ]
+/// [ const [ 4 ] ]
+/// [ binding Item [ Vec<T> ] ]
+/// [ constraint Item [ Clone + 'a ] ]
+/// [ binding Item<'a> [ &'a T ] ]
+/// ```
+///
+/// A bare identifier like `N` is indistinguishable from a type at this point, so it is
+/// reported as `type`.
+///
+/// # Examples
+///
+/// ```rust
+/// #[doc(hidden)]
+/// pub use generics2::parse_args as generics_parse_args;
+///
+/// #[macro_export]
+/// macro_rules! count_types {
+///     (
+///         @count $name:ident [$([$kind:ident $($arg:tt)*])*]
+///     ) => {
+///         pub const $name: usize = 0 $(+ $crate::count_types!(@is_type $kind))*;
+///     };
+///     (@is_type type) => { 1 };
+///     (@is_type $kind:ident) => { 0 };
+///     (
+///         $name:ident $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_args! {
+///             $crate::count_types {
+///                 @count $name
+///             }
+///             $($token)*
+///         }
+///     };
+/// }
+///
+/// count_types! { TYPES <'a, Vec<u8>, { 1 + 2 }, Item = u16> }
+/// # fn main() { assert_eq!(TYPES, 1); }
+/// ```
+#[macro_export]
+macro_rules! parse_args {
+    (
+        $callback:path { $($callback_args:tt)* } < $($token:tt)*
+    ) => {
+        $crate::parse_args_impl! { @start [$callback] [$($callback_args)*] [] [$($token)*] }
+    };
+    (
+        $callback:path { $($callback_args:tt)* } $($token:tt)*
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            []
+            $($token)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_args_impl {
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($args)*]
+            $($token)*
+        }
+    };
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($args)*]
+            > $($token)*
+        }
+    };
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ $arg:lifetime $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @separator
+            [$callback] [$($callback_args)*] [$($args)* [lifetime [$arg]]]
+            [$($token)*]
+        }
+    };
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ { $($arg:tt)* } $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @separator
+            [$callback] [$($callback_args)*] [$($args)* [const [{ $($arg)* }]]]
+            [$($token)*]
+        }
+    };
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ $arg:literal $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @separator
+            [$callback] [$($callback_args)*] [$($args)* [const [$arg]]]
+            [$($token)*]
+        }
+    };
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ $name:ident = $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [binding $name] ["type"]
+            [$($token)*]
+        }
+    };
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ $name:ident : $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [constraint $name] ["type"]
+            [$($token)*]
+        }
+    };
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ $name:ident < $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @assoc
+            [$callback] [$($callback_args)*] [$($args)*] [$name <] [<]
+            [$($token)*] [$name < $($token)*]
+        }
+    };
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!("missing '>'");
+    };
+    (
+        @start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [  $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [type] ["lifetime, type, const, or associated item"]
+            [$($token)*]
+        }
+    };
+    (
+        @arg_start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*] [$expected:literal]
+        [ , $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! { @unexpected [$expected] [,] }
+    };
+    (
+        @arg_start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*] [$expected:literal]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! { @unexpected [$expected] [>] }
+    };
+    (
+        @arg_start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*] [$expected:literal]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! { @unexpected [$expected] [>>] }
+    };
+    (
+        @arg_start
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*] [$expected:literal]
+        [$($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($args)*] [$($kind)*]
+            [] [<]
+            [$($token)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [<] [ > = $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [binding $($name)* >] ["type"]
+            [$($token)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [<] [ >= $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [binding $($name)* >] ["type"]
+            [$($token)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [<] [ > : $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [constraint $($name)* >] ["type"]
+            [$($token)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [< <] [ >> = $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [binding $($name)* >>] ["type"]
+            [$($token)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [< <] [ >> : $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [constraint $($name)* >>] ["type"]
+            [$($token)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [<] [ >> $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [type] ["lifetime, type, const, or associated item"]
+            [$($original)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [<] [ > $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [type] ["lifetime, type, const, or associated item"]
+            [$($original)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [< <] [ >> $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [type] ["lifetime, type, const, or associated item"]
+            [$($original)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [< $($depth:tt)+] [ > $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @assoc
+            [$callback] [$($callback_args)*] [$($args)*] [$($name)* >] [$($depth)+]
+            [$($token)*] [$($original)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [< < $($depth:tt)+] [ >> $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @assoc
+            [$callback] [$($callback_args)*] [$($args)*] [$($name)* >>] [$($depth)+]
+            [$($token)*] [$($original)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [$($depth:tt)+] [ < $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @assoc
+            [$callback] [$($callback_args)*] [$($args)*] [$($name)* <] [< $($depth)+]
+            [$($token)*] [$($original)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [$($depth:tt)+] [ << $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @assoc
+            [$callback] [$($callback_args)*] [$($args)*] [$($name)* <<] [< < $($depth)+]
+            [$($token)*] [$($original)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [$($depth:tt)+] [ $x:tt $($token:tt)*]
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @assoc
+            [$callback] [$($callback_args)*] [$($args)*] [$($name)* $x] [$($depth)+]
+            [$($token)*] [$($original)*]
+        }
+    };
+    (
+        @assoc
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($name:tt)*] [$($depth:tt)+] []
+        [$($original:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg_start
+            [$callback] [$($callback_args)*] [$($args)*] [type] ["lifetime, type, const, or associated item"]
+            [$($original)*]
+        }
+    };
+    (
+        @unexpected [$expected:literal] [$x:tt]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "', expected ",
+            $expected
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @separator
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ , $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @start
+            [$callback] [$($callback_args)*] [$($args)*]
+            [$($token)*]
+        }
+    };
+    (
+        @separator
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($args)*]
+            $($token)*
+        }
+    };
+    (
+        @separator
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($args)*]
+            > $($token)*
+        }
+    };
+    (
+        @separator
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($x),
+            "', expected ',' or '>'"
+        ));
+        $crate::error_at_impl! { [] $x }
+    };
+    (
+        @separator
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!("missing '>'");
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)+] [<]
+        [ , $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @start
+            [$callback] [$($callback_args)*] [$($args)* [$($kind)* [$($arg)+]]]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)+] [<]
+        [ > $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($args)* [$($kind)* [$($arg)+]]]
+            $($token)*
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)+] [<]
+        [ >> $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($args)* [$($kind)* [$($arg)+]]]
+            > $($token)*
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)+] [< <]
+        [ >> $($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($args)* [$($kind)* [$($arg)+ >]]]
+            $($token)*
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)*] [$($depth:tt)*]
+        []
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "missing '>' after '",
+            $crate::std_stringify!($($arg)*),
+            "'"
+        ));
+        $crate::error_at_impl! { [] $($arg)* }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)*] [$($depth:tt)*]
+        [ < $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($args)*] [$($kind)*]
+            [$($arg)* <] [< $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)*] [$($depth:tt)*]
+        [ << $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($args)*] [$($kind)*]
+            [$($arg)* <<] [< < $($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)*] [< $($depth:tt)*]
+        [ > $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($args)*] [$($kind)*]
+            [$($arg)* >] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)*] [< < $($depth:tt)*]
+        [ >> $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($args)*] [$($kind)*]
+            [$($arg)* >>] [$($depth)*]
+            [$($token)*]
+        }
+    };
+    (
+        @arg
+        [$callback:path] [$($callback_args:tt)*] [$($args:tt)*] [$($kind:tt)*]
+        [$($arg:tt)*] [$($depth:tt)*]
+        [$x:tt $($token:tt)*]
+    ) => {
+        $crate::parse_args_impl! {
+            @arg
+            [$callback] [$($callback_args)*] [$($args)*] [$($kind)*]
+            [$($arg)* $x] [$($depth)*]
+            [$($token)*]
+        }
+    };
+}
+
+/// Adds a bound to every type parameter of a [`parse_raw`](parse_raw) result.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( where )? [ $($bound)+ ]
+/// [ $($parse_raw_result)* ]
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $($parse_raw_result_with_bounds)* ]
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// The bound is appended to the existing constraints of each type parameter, in the generics
+/// list as well as in the per-parameter and declaration lists, while lifetime and const
+/// parameters are left alone. With a leading `where`, the generics are kept as they are and
+/// a `T: $bound` predicate is appended to the where clause for each type parameter instead.
+///
+/// # Examples
+///
+/// ```rust
+/// #[doc(hidden)]
+/// pub use generics2::parse_raw as generics_parse_raw;
+/// #[doc(hidden)]
+/// pub use generics2::add_bounds as generics_add_bounds;
+///
+/// #[macro_export]
+/// macro_rules! derive_clone {
+///     (
+///         $name:ident $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_raw! {
+///             $crate::derive_clone {
+///                 @parsed $name
+///             }
+///             $($token)*
+///         }
+///     };
+///     (
+///         @parsed $name:ident [$($parsed:tt)*] ($($field:tt)*);
+///     ) => {
+///         $crate::generics_add_bounds! {
+///             $crate::derive_clone {
+///                 @impl $name
+///             }
+///             [Clone]
+///             [$($parsed)*]
+///         }
+///     };
+///     (
+///         @impl $name:ident [[$([$([$($g:tt)*])*])?] [$([$([$($r:tt)*])*])?] [$(where $($w:tt)*)?] $($extra:tt)*]
+///     ) => {
+///         impl $(<$($($g)*),*>)? Clone for $name $(<$($($r)*),*>)? $(where $($w)*)? {
+///             fn clone(&self) -> Self { Self(self.0, self.1.clone()) }
+///         }
+///     };
+/// }
+///
+/// pub struct Pair<'a, T, U: Default>(&'a T, U);
+///
+/// derive_clone! { Pair<'a, T, U: Default>(&'a T, U); }
+/// # fn main() { }
+/// ```
+#[macro_export]
+macro_rules! add_bounds {
+    (
+        $callback:path { $($callback_args:tt)* }
+        where [$($bound:tt)+]
+        [[$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [$($params:tt)*] [$($preds:tt)*] $($extra:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::add_bounds_impl! {
+            @where
+            [$callback] [$($callback_args)*] [$($bound)+]
+            [] [$($params)*]
+            [[$($g)*] [$($r)*] [$($w)*] [$($params)*] [$($preds)*] $($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        [$($bound:tt)+]
+        [
+            [$([$($g:tt)*])?] [$($r:tt)*] [$($w:tt)*] [$($params:tt)*] [$($preds:tt)*]
+            $decl:tt [$($attrs:tt)*] $($extra:tt)*
+        ]
+        $($token:tt)*
+    ) => {
+        $crate::add_bounds_impl! {
+            @bound
+            [$callback] [$($callback_args)*] [$($bound)+]
+            [] []
+            [$($($g)*)?] [$($params)*] [$($attrs)*]
+            [[$($r)*] [$($w)*] [$($preds)*] $decl [$($attrs)*] $($extra)*]
+            [$($token)*]
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_bounds_impl {
+    (
+        @bound
+        [$callback:path] [$($callback_args:tt)*] [$($bound:tt)+]
+        [$($g:tt)*] [$($params:tt)*]
+        [$gparam:tt $($gparams:tt)*] [[type $name:tt [] [$($default:tt)*]] $($sparams:tt)*]
+        [[$($attr:tt)*] $($attrs:tt)*]
+        [$($others:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::add_bounds_impl! {
+            @bound
+            [$callback] [$($callback_args)*] [$($bound)+]
+            [$($g)* [$($attr)* $name : $($bound)+]] [$($params)* [type $name [$($bound)+] [$($default)*]]]
+            [$($gparams)*] [$($sparams)*] [$($attrs)*]
+            [$($others)*]
+            [$($token)*]
+        }
+    };
+    (
+        @bound
+        [$callback:path] [$($callback_args:tt)*] [$($bound:tt)+]
+        [$($g:tt)*] [$($params:tt)*]
+        [$gparam:tt $($gparams:tt)*] [[type $name:tt [$($constraint:tt)+] [$($default:tt)*]] $($sparams:tt)*]
+        [[$($attr:tt)*] $($attrs:tt)*]
+        [$($others:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::add_bounds_impl! {
+            @bound
+            [$callback] [$($callback_args)*] [$($bound)+]
+            [$($g)* [$($attr)* $name : $($constraint)+ + $($bound)+]] [$($params)* [type $name [$($constraint)+ + $($bound)+] [$($default)*]]]
+            [$($gparams)*] [$($sparams)*] [$($attrs)*]
+            [$($others)*]
+            [$($token)*]
+        }
+    };
+    (
+        @bound
+        [$callback:path] [$($callback_args:tt)*] [$($bound:tt)+]
+        [$($g:tt)*] [$($params:tt)*]
+        [$gparam:tt $($gparams:tt)*] [$sparam:tt $($sparams:tt)*] [$attr:tt $($attrs:tt)*]
+        [$($others:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::add_bounds_impl! {
+            @bound
+            [$callback] [$($callback_args)*] [$($bound)+]
+            [$($g)* $gparam] [$($params)* $sparam]
+            [$($gparams)*] [$($sparams)*] [$($attrs)*]
+            [$($others)*]
+            [$($token)*]
+        }
+    };
+    (
+        @bound
+        [$callback:path] [$($callback_args:tt)*] [$($bound:tt)+]
+        [] []
+        [] [] []
+        [[$($r:tt)*] [$($w:tt)*] [$($preds:tt)*] $($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [[] [$($r)*] [$($w)*] [] [$($preds)*] $($extra)*]
+            $($token)*
+        }
+    };
+    (
+        @bound
+        [$callback:path] [$($callback_args:tt)*] [$($bound:tt)+]
+        [$([$($g:tt)*])+] [$([$kind:tt $name:tt [$($constraint:tt)*] [$($($default:tt)+)?]])+]
+        [] [] []
+        [[$($r:tt)*] [$($w:tt)*] [$($preds:tt)*] [$($d:tt)*] [$([$($attr:tt)*])+] $($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [
+                [[$([$($g)*])+]]
+                [$($r)*]
+                [$($w)*]
+                [$([$kind $name [$($constraint)*] [$($($default)+)?]])+]
+                [$($preds)*]
+                [[$([$($g)* $(= $($default)+)?])+]]
+                [$([$($attr)*])+]
+                $($extra)*
+            ]
+            $($token)*
+        }
+    };
+    (
+        @where
+        [$callback:path] [$($callback_args:tt)*] [$($bound:tt)+]
+        [$($new:tt)*] [[type $name:tt $($param:tt)*] $($params:tt)*]
+        [$($parsed:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::add_bounds_impl! {
+            @where
+            [$callback] [$($callback_args)*] [$($bound)+]
+            [$($new)* [[$name] [$($bound)+]]] [$($params)*]
+            [$($parsed)*]
+            [$($token)*]
+        }
+    };
+    (
+        @where
+        [$callback:path] [$($callback_args:tt)*] [$($bound:tt)+]
+        [$($new:tt)*] [$param:tt $($params:tt)*]
+        [$($parsed:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::add_bounds_impl! {
+            @where
+            [$callback] [$($callback_args)*] [$($bound)+]
+            [$($new)*] [$($params)*]
+            [$($parsed)*]
+            [$($token)*]
+        }
+    };
+    (
+        @where
+        [$callback:path] [$($callback_args:tt)*] [$($bound:tt)+]
+        [$([[$($new_ty:tt)*] [$($new_bounds:tt)*]])*] []
+        [[$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [$($params:tt)*] [$([[$($ty:tt)*] [$($bounds:tt)*]])*] $($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::add_bounds_impl! {
+            @emit
+            [$callback] [$($callback_args)*]
+            [[$($g)*] [$($r)*]]
+            [$([[$($ty)*] [$($bounds)*]])* $([[$($new_ty)*] [$($new_bounds)*]])*]
+            [[$($params)*]]
+            [$($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        @emit
+        [$callback:path] [$($callback_args:tt)*]
+        [$($gr:tt)*]
+        [$($([[$($ty:tt)*] [$($bounds:tt)*]])+)?]
+        [$($params:tt)*]
+        [$($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [
+                $($gr)*
+                [$(where $($($ty)* : $($bounds)*),+)?]
+                $($params)*
+                [$($([[$($ty)*] [$($bounds)*]])+)?]
+                $($extra)*
+            ]
+            $($token)*
+        }
+    };
+}
+
+/// Builds a `PhantomData` marker type covering every parameter of a [`parse_raw`](parse_raw) result.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( covariant | invariant | contravariant )?
+/// [ $($parse_raw_result)* ]
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ PhantomData<( $( $marker, )* )> ]
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// with one `$marker` per lifetime and type parameter, in declaration order. Const parameters
+/// are skipped. The variance of the marker in each parameter is chosen by the mode, which
+/// defaults to `covariant`:
+///
+/// | mode            | lifetime `'a`              | type `T`          |
+/// |-----------------|----------------------------|-------------------|
+/// | `covariant`     | `&'a ()`                   | `T`               |
+/// | `invariant`     | `fn(&'a ()) -> &'a ()`     | `fn(T) -> T`      |
+/// | `contravariant` | `fn(&'a ())`               | `fn(T)`           |
+///
+/// A type parameter flagged `unsized` by [`parse_raw`](parse_raw), or listed among the
+/// parameters relaxed by its where clause, is wrapped in its own `PhantomData` first, so
+/// `T: ?Sized` gives `PhantomData<T>`, `fn(PhantomData<T>) -> PhantomData<T>` or
+/// `fn(PhantomData<T>)`, and the tuple stays `Sized`. Matching the where clause names to
+/// their parameters needs a local helper macro, so this macro must be expanded in item
+/// position when a where clause predicate relaxes a bare parameter.
+///
+/// The `PhantomData` path goes through this crate's re-export of `core::marker::PhantomData`,
+/// so the expansion also works in `no_std` crates.
+///
+/// # Examples
+///
+/// ```rust
+/// #[doc(hidden)]
+/// pub use generics2::parse_raw as generics_parse_raw;
+/// #[doc(hidden)]
+/// pub use generics2::phantom_marker as generics_phantom_marker;
+///
+/// #[macro_export]
+/// macro_rules! handle {
+///     (
+///         $name:ident $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_raw! {
+///             $crate::handle {
+///                 @parsed $name
+///             }
+///             $($token)*
+///         }
+///     };
+///     (
+///         @parsed $name:ident [$($parsed:tt)*] ;
+///     ) => {
+///         $crate::generics_phantom_marker! {
+///             $crate::handle {
+///                 @struct $name [$($parsed)*]
+///             }
+///             invariant [$($parsed)*]
+///         }
+///     };
+///     (
+///         @struct $name:ident [[$([$([$($g:tt)*])*])?] [$([$([$($r:tt)*])*])?] [$(where $($w:tt)*)?] $($extra:tt)*]
+///         [$($marker:tt)*]
+///     ) => {
+///         pub struct $name $(<$($($g)*),*>)? $(where $($w)*)? {
+///             pub id: usize,
+///             marker: $($marker)*,
+///         }
+///     };
+/// }
+///
+/// handle! { Handle<'a, T: 'a, const N: usize>; }
+/// # fn main() { }
+/// ```
+#[macro_export]
+macro_rules! phantom_marker {
+    (
+        $callback:path { $($callback_args:tt)* }
+        covariant [
+            [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [$($params:tt)*]
+            [$($preds:tt)*] [$($decl:tt)*] [$($attrs:tt)*] [$($sized:tt)*] [$($relaxed:tt)*]
+            $($extra:tt)*
+        ]
+        $($token:tt)*
+    ) => {
+        $crate::phantom_marker_impl! {
+            [$callback] [$($callback_args)*] [covariant]
+            [$($params)*] [$($sized)*] [$($relaxed)*]
+            [$($token)*]
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        invariant [
+            [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [$($params:tt)*]
+            [$($preds:tt)*] [$($decl:tt)*] [$($attrs:tt)*] [$($sized:tt)*] [$($relaxed:tt)*]
+            $($extra:tt)*
+        ]
+        $($token:tt)*
+    ) => {
+        $crate::phantom_marker_impl! {
+            [$callback] [$($callback_args)*] [invariant]
+            [$($params)*] [$($sized)*] [$($relaxed)*]
+            [$($token)*]
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        contravariant [
+            [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [$($params:tt)*]
+            [$($preds:tt)*] [$($decl:tt)*] [$($attrs:tt)*] [$($sized:tt)*] [$($relaxed:tt)*]
+            $($extra:tt)*
+        ]
+        $($token:tt)*
+    ) => {
+        $crate::phantom_marker_impl! {
+            [$callback] [$($callback_args)*] [contravariant]
+            [$($params)*] [$($sized)*] [$($relaxed)*]
+            [$($token)*]
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        [
+            [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [$($params:tt)*]
+            [$($preds:tt)*] [$($decl:tt)*] [$($attrs:tt)*] [$($sized:tt)*] [$($relaxed:tt)*]
+            $($extra:tt)*
+        ]
+        $($token:tt)*
+    ) => {
+        $crate::phantom_marker_impl! {
+            [$callback] [$($callback_args)*] [covariant]
+            [$($params)*] [$($sized)*] [$($relaxed)*]
+            [$($token)*]
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        $mode:tt $($token:tt)*
+    ) => {
+        $crate::std_compile_error!($crate::std_concat!(
+            "unexpected token '",
+            $crate::std_stringify!($mode),
+            "', expected 'covariant', 'invariant', or 'contravariant'"
+        ));
+        $crate::error_at_impl! { [] $mode }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! phantom_marker_impl {
+    (
+        [$callback:path] [$($callback_args:tt)*] [$mode:ident]
+        [$($params:tt)*] [$($sized:tt)*] []
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [$mode]
+            []
+            [$($params)*] [$($sized)*]
+            [$($token)*]
+        }
+    };
+    (
+        [$callback:path] [$($callback_args:tt)*] [$mode:ident]
+        [$($params:tt)*] [$($sized:tt)*] [$($relaxed:ident)+]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @relaxed [$]
+            [$callback] [$($callback_args)*] [$mode]
+            [$($params)*] [$($sized)*] [$($relaxed)+]
+            [$($token)*]
+        }
+    };
+    (
+        @relaxed [$d:tt]
+        [$callback:path] [$($callback_args:tt)*] [$mode:ident]
+        [$($params:tt)*] [$($sized:tt)*] [$($relaxed:ident)+]
+        [$($token:tt)*]
+    ) => {
+        macro_rules! __generics2_relaxed {
+            $(
+                (
+                    [$d($done:tt)*] [[type $relaxed $d($param:tt)*] $d($next:tt)*]
+                    [$d flag:tt $d($flags:tt)*]
+                ) => {
+                    __generics2_relaxed! {
+                        [$d($done)* [unsized]] [$d($next)*] [$d($flags)*]
+                    }
+                };
+            )+
+            (
+                [$d($done:tt)*] [$d param:tt $d($next:tt)*] [$d flag:tt $d($flags:tt)*]
+            ) => {
+                __generics2_relaxed! {
+                    [$d($done)* $d flag] [$d($next)*] [$d($flags)*]
+                }
+            };
+            (
+                [$d($done:tt)*] [] []
+            ) => {
+                $crate::phantom_marker_impl! {
+                    @param
+                    [$callback] [$($callback_args)*] [$mode]
+                    []
+                    [$($params)*] [$d($done)*]
+                    [$($token)*]
+                }
+            };
+        }
+
+        __generics2_relaxed! { [] [$($params)*] [$($sized)*] }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [covariant]
+        [$($marker:tt)*]
+        [[lifetime $name:lifetime $($param:tt)*] $($params:tt)*] [$flag:tt $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [covariant]
+            [$($marker)* [&$name ()]]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [covariant]
+        [$($marker:tt)*]
+        [[type $name:ident $($param:tt)*] $($params:tt)*] [[sized] $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [covariant]
+            [$($marker)* [$name]]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [covariant]
+        [$($marker:tt)*]
+        [[type $name:ident $($param:tt)*] $($params:tt)*] [[unsized] $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [covariant]
+            [$($marker)* [$crate::std_PhantomData<$name>]]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [invariant]
+        [$($marker:tt)*]
+        [[lifetime $name:lifetime $($param:tt)*] $($params:tt)*] [$flag:tt $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [invariant]
+            [$($marker)* [fn(&$name ()) -> &$name ()]]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [invariant]
+        [$($marker:tt)*]
+        [[type $name:ident $($param:tt)*] $($params:tt)*] [[sized] $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [invariant]
+            [$($marker)* [fn($name) -> $name]]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [invariant]
+        [$($marker:tt)*]
+        [[type $name:ident $($param:tt)*] $($params:tt)*] [[unsized] $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [invariant]
+            [$($marker)* [fn($crate::std_PhantomData<$name>) -> $crate::std_PhantomData<$name>]]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [contravariant]
+        [$($marker:tt)*]
+        [[lifetime $name:lifetime $($param:tt)*] $($params:tt)*] [$flag:tt $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [contravariant]
+            [$($marker)* [fn(&$name ())]]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [contravariant]
+        [$($marker:tt)*]
+        [[type $name:ident $($param:tt)*] $($params:tt)*] [[sized] $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [contravariant]
+            [$($marker)* [fn($name)]]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [contravariant]
+        [$($marker:tt)*]
+        [[type $name:ident $($param:tt)*] $($params:tt)*] [[unsized] $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [contravariant]
+            [$($marker)* [fn($crate::std_PhantomData<$name>)]]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [$mode:ident]
+        [$($marker:tt)*]
+        [[const $($param:tt)*] $($params:tt)*] [$flag:tt $($flags:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::phantom_marker_impl! {
+            @param
+            [$callback] [$($callback_args)*] [$mode]
+            [$($marker)*]
+            [$($params)*] [$($flags)*]
+            [$($token)*]
+        }
+    };
+    (
+        @param
+        [$callback:path] [$($callback_args:tt)*] [$mode:ident]
+        [$([$($marker:tt)*])*]
+        [] []
+        [$($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$crate::std_PhantomData<($($($marker)*,)*)>]
+            $($token)*
+        }
+    };
+}
+
+/// Keeps only the lifetime parameters of a [`parse_raw`](parse_raw) result.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// [ $($parse_raw_result)* ]
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( < $lifetimes > )? ]
+///     [ $( < $lifetimes_without_constraints > )? ]
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// Both brackets are empty when there is no matching parameter.
+///
+/// # Examples
+///
+/// ```rust
+/// #[doc(hidden)]
+/// pub use generics2::parse_raw as generics_parse_raw;
+/// #[doc(hidden)]
+/// pub use generics2::filter_lifetimes as generics_filter_lifetimes;
+///
+/// #[macro_export]
+/// macro_rules! callback_fn {
+///     (
+///         $name:ident $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_raw! {
+///             $crate::callback_fn {
+///                 @parsed $name
+///             }
+///             $($token)*
+///         }
+///     };
+///     (
+///         @parsed $name:ident [$($parsed:tt)*] ($($arg:tt)*)
+///     ) => {
+///         $crate::generics_filter_lifetimes! {
+///             $crate::callback_fn {
+///                 @fn $name
+///             }
+///             [$($parsed)*]
+///             ($($arg)*)
+///         }
+///     };
+///     (
+///         @fn $name:ident [$($g:tt)*] [$($r:tt)*] ($($arg:tt)*)
+///     ) => {
+///         pub fn $name(f: impl for $($r)* Fn($($arg)*)) -> impl for $($r)* Fn($($arg)*) { f }
+///     };
+/// }
+///
+/// callback_fn! { callback<'a, 'b: 'a, T> (&'a u8, &'b str) }
+/// # fn main() { let _ = callback(|_: &u8, _: &str| ()); }
+/// ```
+#[macro_export]
+macro_rules! filter_lifetimes {
+    (
+        $callback:path { $($callback_args:tt)* }
+        [[$([$($g:tt)*])?] [$([$($r:tt)*])?] [$($w:tt)*] [$($params:tt)*] $($extra:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::filter_impl! {
+            [$callback] [$($callback_args)*] [lifetime]
+            [] []
+            [$($($g)*)?] [$($($r)*)?] [$($params)*]
+            [$($token)*]
+        }
+    };
+}
+
+/// Keeps only the type parameters of a [`parse_raw`](parse_raw) result.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// [ $($parse_raw_result)* ]
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( < $types > )? ]
+///     [ $( < $types_without_constraints > )? ]
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// Both brackets are empty when there is no matching parameter.
+#[macro_export]
+macro_rules! filter_types {
+    (
+        $callback:path { $($callback_args:tt)* }
+        [[$([$($g:tt)*])?] [$([$($r:tt)*])?] [$($w:tt)*] [$($params:tt)*] $($extra:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::filter_impl! {
+            [$callback] [$($callback_args)*] [type]
+            [] []
+            [$($($g)*)?] [$($($r)*)?] [$($params)*]
+            [$($token)*]
+        }
+    };
+}
+
+/// Keeps only the const parameters of a [`parse_raw`](parse_raw) result.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// [ $($parse_raw_result)* ]
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $( < $consts > )? ]
+///     [ $( < $consts_without_constraints > )? ]
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// Both brackets are empty when there is no matching parameter.
+#[macro_export]
+macro_rules! filter_consts {
+    (
+        $callback:path { $($callback_args:tt)* }
+        [[$([$($g:tt)*])?] [$([$($r:tt)*])?] [$($w:tt)*] [$($params:tt)*] $($extra:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::filter_impl! {
+            [$callback] [$($callback_args)*] [const]
+            [] []
+            [$($($g)*)?] [$($($r)*)?] [$($params)*]
+            [$($token)*]
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! filter_impl {
+    (
+        [$callback:path] [$($callback_args:tt)*] [lifetime]
+        [$($g:tt)*] [$($r:tt)*]
+        [$gparam:tt $($gparams:tt)*] [$rparam:tt $($rparams:tt)*] [[lifetime $($param:tt)*] $($params:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::filter_impl! {
+            [$callback] [$($callback_args)*] [lifetime]
+            [$($g)* $gparam] [$($r)* $rparam]
+            [$($gparams)*] [$($rparams)*] [$($params)*]
+            [$($token)*]
+        }
+    };
+    (
+        [$callback:path] [$($callback_args:tt)*] [type]
+        [$($g:tt)*] [$($r:tt)*]
+        [$gparam:tt $($gparams:tt)*] [$rparam:tt $($rparams:tt)*] [[type $($param:tt)*] $($params:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::filter_impl! {
+            [$callback] [$($callback_args)*] [type]
+            [$($g)* $gparam] [$($r)* $rparam]
+            [$($gparams)*] [$($rparams)*] [$($params)*]
+            [$($token)*]
+        }
+    };
+    (
+        [$callback:path] [$($callback_args:tt)*] [const]
+        [$($g:tt)*] [$($r:tt)*]
+        [$gparam:tt $($gparams:tt)*] [$rparam:tt $($rparams:tt)*] [[const $($param:tt)*] $($params:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::filter_impl! {
+            [$callback] [$($callback_args)*] [const]
+            [$($g)* $gparam] [$($r)* $rparam]
+            [$($gparams)*] [$($rparams)*] [$($params)*]
+            [$($token)*]
+        }
+    };
+    (
+        [$callback:path] [$($callback_args:tt)*] [$kind:ident]
+        [$($g:tt)*] [$($r:tt)*]
+        [$gparam:tt $($gparams:tt)*] [$rparam:tt $($rparams:tt)*] [$param:tt $($params:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::filter_impl! {
+            [$callback] [$($callback_args)*] [$kind]
+            [$($g)*] [$($r)*]
+            [$($gparams)*] [$($rparams)*] [$($params)*]
+            [$($token)*]
+        }
+    };
+    (
+        [$callback:path] [$($callback_args:tt)*] [$kind:ident]
+        [$($([$($g:tt)*])+)?] [$($([$($r:tt)*])+)?]
+        [] [] []
+        [$($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$(< $($($g)*),+ >)?]
+            [$(< $($($r)*),+ >)?]
+            $($token)*
+        }
+    };
+}
+
+/// Renames generic parameters everywhere in a [`parse_raw`](parse_raw) result.
+///
+/// This macro accepts an input in the following form:
+///
+/// ```ignore
+/// $callback_macro { $($callback_macro_args)* }
+/// $( [ $( $old_name => $new_name ),+ $(,)? ] | hygienic $( [ $($names)* ] [ $($lifetimes)* ] )? )
+/// [ $($parse_raw_result)* ]
+/// $($remaining_tokens)*
+/// ```
+///
+/// and expands into
+///
+/// ```ignore
+/// $callback_macro! {
+///     $( $callback_macro_args )*
+///     [ $($renamed_parse_raw_result)* ]
+///     $($remaining_tokens)*
+/// }
+/// ```
+///
+/// Every occurrence of `$old_name` is replaced, in the generics as well as inside bounds and
+/// where clauses, so `<T, U: Into<T>>` renamed with `[T => __T0]` becomes `<__T0, U: Into<__T0>>`.
+/// Lifetimes are renamed the same way, as in `['a => 'x]`. All names are replaced in a single
+/// pass, so `[T => U, U => T]` swaps the two parameters. The `$remaining_tokens` are left
+/// untouched.
+///
+/// In `hygienic` mode every parameter of the list is renamed at once: type and const
+/// parameters become `__G0`, `__G1`, ... and lifetimes become `'__g0`, `'__g1`, ...,
+/// numbered in declaration order. `macro_rules!` cannot build new identifiers, so
+/// these names come from a fixed pool of 32 names per kind. Two lists renamed this way get the
+/// same names, so lists that are later passed together to [`concat`](concat) must each be
+/// given pools of their own, as in `hygienic [A0 A1 A2] ['a0 'a1] [...]`, used in the same
+/// order.
+///
+/// The renaming is done by a helper `macro_rules!` defined next to the expansion, so this
+/// macro can only be used where items are allowed.
+///
+/// # Examples
+///
+/// ```rust
+/// #[doc(hidden)]
+/// pub use generics2::parse_raw as generics_parse_raw;
+/// #[doc(hidden)]
+/// pub use generics2::rename as generics_rename;
+///
+/// pub trait Named { }
+///
+/// #[macro_export]
+/// macro_rules! impl_named {
+///     (
+///         $name:ident $($token:tt)*
+///     ) => {
+///         $crate::generics_parse_raw! {
+///             $crate::impl_named {
+///                 @parsed $name
+///             }
+///             $($token)*
+///         }
+///     };
+///     (
+///         @parsed $name:ident [$($parsed:tt)*]
+///     ) => {
+///         $crate::generics_rename! {
+///             $crate::impl_named {
+///                 @impl $name
+///             }
+///             hygienic [$($parsed)*]
+///         }
+///     };
+///     (
+///         @impl $name:ident [[$([$([$($g:tt)*])*])?] [$([$([$($r:tt)*])*])?] [$(where $($w:tt)*)?] $($extra:tt)*]
+///     ) => {
+///         impl $(<$($($g)*),*>)? $crate::Named for $name $(<$($($r)*),*>)? $(where $($w)*)? { }
+///     };
+/// }
+///
+/// pub struct Wrapper<'a, T>(&'a T);
+///
+/// // Expands to `impl<'__g0, __G0: '__g0> Named for Wrapper<'__g0, __G0> { }`.
+/// impl_named! { Wrapper<'a, T: 'a> }
+/// # fn main() { }
+/// ```
+#[macro_export]
+macro_rules! rename {
+    (
+        $callback:path { $($callback_args:tt)* }
+        hygienic [$($ty_pool:ident)*] [$($lifetime_pool:lifetime)*]
+        [[$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [$($params:tt)*] $($extra:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::rename_impl! {
+            @hygienic
+            [$callback] [$($callback_args)*]
+            [] [$($params)*]
+            [$($ty_pool)*] [$($lifetime_pool)*]
+            [[$($g)*] [$($r)*] [$($w)*] [$($params)*] $($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        hygienic [[$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [$($params:tt)*] $($extra:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::rename_impl! {
+            @hygienic
+            [$callback] [$($callback_args)*]
+            [] [$($params)*]
+            [__G0 __G1 __G2 __G3 __G4 __G5 __G6 __G7 __G8 __G9 __G10 __G11 __G12 __G13 __G14 __G15 __G16 __G17 __G18 __G19 __G20 __G21 __G22 __G23 __G24 __G25 __G26 __G27 __G28 __G29 __G30 __G31] ['__g0 '__g1 '__g2 '__g3 '__g4 '__g5 '__g6 '__g7 '__g8 '__g9 '__g10 '__g11 '__g12 '__g13 '__g14 '__g15 '__g16 '__g17 '__g18 '__g19 '__g20 '__g21 '__g22 '__g23 '__g24 '__g25 '__g26 '__g27 '__g28 '__g29 '__g30 '__g31]
+            [[$($g)*] [$($r)*] [$($w)*] [$($params)*] $($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        [$($old:tt => $new:tt),+ $(,)?] [$($parsed:tt)*]
+        $($token:tt)*
+    ) => {
+        $crate::rename_impl! {
+            @define [$]
+            [$callback] [$($callback_args)*]
+            [$([$old $new])+]
+            [$($parsed)*]
+            [$($token)*]
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! rename_impl {
+    (
+        @hygienic
+        [$callback:path] [$($callback_args:tt)*]
+        [$($names:tt)*] [[lifetime $name:tt $($param:tt)*] $($params:tt)*]
+        [$($ty_pool:tt)*] [$new:tt $($lifetime_pool:tt)*]
+        [$($parsed:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @hygienic
+            [$callback] [$($callback_args)*]
+            [$($names)* [$name $new]] [$($params)*]
+            [$($ty_pool)*] [$($lifetime_pool)*]
+            [$($parsed)*]
+            [$($token)*]
+        }
+    };
+    (
+        @hygienic
+        [$callback:path] [$($callback_args:tt)*]
+        [$($names:tt)*] [[lifetime $name:tt $($param:tt)*] $($params:tt)*]
+        [$($ty_pool:tt)*] []
+        [$($parsed:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::std_compile_error!("too many lifetime parameters for hygienic renaming");
+    };
+    (
+        @hygienic
+        [$callback:path] [$($callback_args:tt)*]
+        [$($names:tt)*] [[$kind:ident $name:tt $($param:tt)*] $($params:tt)*]
+        [$new:tt $($ty_pool:tt)*] [$($lifetime_pool:tt)*]
+        [$($parsed:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @hygienic
+            [$callback] [$($callback_args)*]
+            [$($names)* [$name $new]] [$($params)*]
+            [$($ty_pool)*] [$($lifetime_pool)*]
+            [$($parsed)*]
+            [$($token)*]
+        }
+    };
+    (
+        @hygienic
+        [$callback:path] [$($callback_args:tt)*]
+        [$($names:tt)*] [[$kind:ident $name:tt $($param:tt)*] $($params:tt)*]
+        [] [$($lifetime_pool:tt)*]
+        [$($parsed:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::std_compile_error!("too many type and const parameters for hygienic renaming");
+    };
+    (
+        @hygienic
+        [$callback:path] [$($callback_args:tt)*]
+        [] []
+        [$($ty_pool:tt)*] [$($lifetime_pool:tt)*]
+        [$($parsed:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($parsed)*]
+            $($token)*
+        }
+    };
+    (
+        @hygienic
+        [$callback:path] [$($callback_args:tt)*]
+        [$($names:tt)+] []
+        [$($ty_pool:tt)*] [$($lifetime_pool:tt)*]
+        [$($parsed:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @define [$]
+            [$callback] [$($callback_args)*]
+            [$($names)+]
+            [$($parsed)*]
+            [$($token)*]
+        }
+    };
+    (
+        @define [$d:tt]
+        [$callback:path] [$($callback_args:tt)*]
+        [$([$old:tt $new:tt])+]
+        [
+            [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] [$([$($param:tt)*])*] [$([$($pred:tt)*])*] [$($decl:tt)*]
+            [$($attrs:tt)*] [$($sized:tt)*] [$($relaxed:tt)*] $($extra:tt)*
+        ]
+        [$($token:tt)*]
+    ) => {
+        macro_rules! __generics2_rename {
+            $(
+                (
+                    @token [$d($acc:tt)*] [$old $d($rest:tt)*] [$d($stack:tt)*]
+                ) => {
+                    __generics2_rename! {
+                        @token [$d($acc)* $new] [$d($rest)*] [$d($stack)*]
+                    }
+                };
+            )+
+            (
+                @token [$d($acc:tt)*] [($d($inner:tt)+) $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[paren [$d($acc)*] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [[$d($inner:tt)+] $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[bracket [$d($acc)*] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [{$d($inner:tt)+} $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[brace [$d($acc)*] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            $(
+                (
+                    @token [$d($acc:tt)*] [$d x0:tt $old $d($rest:tt)*] [$d($stack:tt)*]
+                ) => {
+                    __generics2_rename! {
+                        @token [$d($acc)* $d x0 $new] [$d($rest)*] [$d($stack)*]
+                    }
+                };
+            )+
+            (
+                @token [$d($acc:tt)*] [$d x0:tt ($d($inner:tt)+) $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[paren [$d($acc)* $d x0] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d x0:tt [$d($inner:tt)+] $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[bracket [$d($acc)* $d x0] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d x0:tt {$d($inner:tt)+} $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[brace [$d($acc)* $d x0] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            $(
+                (
+                    @token [$d($acc:tt)*] [$d x0:tt $d x1:tt $old $d($rest:tt)*] [$d($stack:tt)*]
+                ) => {
+                    __generics2_rename! {
+                        @token [$d($acc)* $d x0 $d x1 $new] [$d($rest)*] [$d($stack)*]
+                    }
+                };
+            )+
+            (
+                @token [$d($acc:tt)*] [$d x0:tt $d x1:tt ($d($inner:tt)+) $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[paren [$d($acc)* $d x0 $d x1] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d x0:tt $d x1:tt [$d($inner:tt)+] $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[bracket [$d($acc)* $d x0 $d x1] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d x0:tt $d x1:tt {$d($inner:tt)+} $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[brace [$d($acc)* $d x0 $d x1] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            $(
+                (
+                    @token [$d($acc:tt)*] [$d x0:tt $d x1:tt $d x2:tt $old $d($rest:tt)*] [$d($stack:tt)*]
+                ) => {
+                    __generics2_rename! {
+                        @token [$d($acc)* $d x0 $d x1 $d x2 $new] [$d($rest)*] [$d($stack)*]
+                    }
+                };
+            )+
+            (
+                @token [$d($acc:tt)*] [$d x0:tt $d x1:tt $d x2:tt ($d($inner:tt)+) $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[paren [$d($acc)* $d x0 $d x1 $d x2] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d x0:tt $d x1:tt $d x2:tt [$d($inner:tt)+] $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[bracket [$d($acc)* $d x0 $d x1 $d x2] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d x0:tt $d x1:tt $d x2:tt {$d($inner:tt)+} $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [] [$d($inner)+] [[brace [$d($acc)* $d x0 $d x1 $d x2] [$d($rest)*]] $d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d x0:tt $d x1:tt $d x2:tt $d x3:tt $d($rest:tt)*] [$d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [$d($acc)* $d x0 $d x1 $d x2 $d x3] [$d($rest)*] [$d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d($x:tt)*] [[paren [$d($outer:tt)*] [$d($rest:tt)*]] $d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [$d($outer)* ($d($acc)* $d($x)*)] [$d($rest)*] [$d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d($x:tt)*] [[bracket [$d($outer:tt)*] [$d($rest:tt)*]] $d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [$d($outer)* [$d($acc)* $d($x)*]] [$d($rest)*] [$d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d($x:tt)*] [[brace [$d($outer:tt)*] [$d($rest:tt)*]] $d($stack:tt)*]
+            ) => {
+                __generics2_rename! {
+                    @token [$d($outer)* {$d($acc)* $d($x)*}] [$d($rest)*] [$d($stack)*]
+                }
+            };
+            (
+                @token [$d($acc:tt)*] [$d($x:tt)*] []
+            ) => {
+                $crate::rename_impl! {
+                    @renamed
+                    [$callback] [$($callback_args)*]
+                    $d($acc)* $d($x)*
+                    [[$($attrs)*] [$($sized)*]] [$($extra)*]
+                    [$($token)*]
+                }
+            };
+        }
+
+        __generics2_rename! {
+            @token [] [[$($($param)*)*] [$($($pred)*)*] [$($relaxed)*]] []
+        }
+    };
+    (
+        @renamed
+        [$callback:path] [$($callback_args:tt)*]
+        $params:tt $preds:tt $relaxed:tt
+        [$($flags:tt)*] [$($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @rebuild
+            [$callback] [$($callback_args)*]
+            [] [] $params $preds
+            [$($flags)* $relaxed $($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        @rebuild
+        [$callback:path] [$($callback_args:tt)*]
+        [$($g:tt)*] [$($done:tt)*] [lifetime $name:tt [] $default:tt $($params:tt)*] $preds:tt
+        [$($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @rebuild
+            [$callback] [$($callback_args)*]
+            [$($g)* [$name]] [$($done)* [lifetime $name [] $default]] [$($params)*] $preds
+            [$($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        @rebuild
+        [$callback:path] [$($callback_args:tt)*]
+        [$($g:tt)*] [$($done:tt)*] [type $name:tt [] [] $($params:tt)*] $preds:tt
+        [$($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @rebuild
+            [$callback] [$($callback_args)*]
+            [$($g)* [$name]] [$($done)* [type $name [] []]] [$($params)*] $preds
+            [$($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        @rebuild
+        [$callback:path] [$($callback_args:tt)*]
+        [$($g:tt)*] [$($done:tt)*] [const $name:tt [$($ty:tt)*] $default:tt $($params:tt)*] $preds:tt
+        [$($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @rebuild
+            [$callback] [$($callback_args)*]
+            [$($g)* [const $name : $($ty)*]] [$($done)* [const $name [$($ty)*] $default]] [$($params)*] $preds
+            [$($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        @rebuild
+        [$callback:path] [$($callback_args:tt)*]
+        [$($g:tt)*] [$($done:tt)*] [$kind:tt $name:tt [$($constraint:tt)*] $default:tt $($params:tt)*] $preds:tt
+        [$($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @rebuild
+            [$callback] [$($callback_args)*]
+            [$($g)* [$name : $($constraint)*]] [$($done)* [$kind $name [$($constraint)*] $default]] [$($params)*] $preds
+            [$($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        @rebuild
+        [$callback:path] [$($callback_args:tt)*]
+        [] [] [] [$([$($ty:tt)*] [$($bounds:tt)*])*]
+        [$($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @emit
+            [$callback] [$($callback_args)*]
+            [[] []] [] [[]]
+            [$([[$($ty)*] [$($bounds)*]])*]
+            [$($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        @rebuild
+        [$callback:path] [$($callback_args:tt)*]
+        [$([$($g:tt)*])+] [$([$kind:tt $name:tt [$($constraint:tt)*] [$($($default:tt)+)?]])+] [] [$([$($ty:tt)*] [$($bounds:tt)*])*]
+        [[$([$($attr:tt)*])+] $($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $crate::rename_impl! {
+            @emit
+            [$callback] [$($callback_args)*]
+            [[[$([$($attr)* $($g)*])+]] [[$([$name])+]]]
+            [$([$kind $name [$($constraint)*] [$($($default)+)?]])+]
+            [[[$([$($attr)* $($g)* $(= $($default)+)?])+]]]
+            [$([[$($ty)*] [$($bounds)*]])*]
+            [[$([$($attr)*])+] $($extra)*]
+            [$($token)*]
+        }
+    };
+    (
+        @emit
+        [$callback:path] [$($callback_args:tt)*]
+        [$($gr:tt)*] [$($params:tt)*] [$($decl:tt)*]
+        [$($([[$($ty:tt)*] [$($bounds:tt)*]])+)?]
+        [$($extra:tt)*]
+        [$($token:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [
+                $($gr)*
+                [$(where $($($ty)* : $($bounds)*),+)?]
+                [$($params)*]
+                [$($([[$($ty)*] [$($bounds)*]])+)?]
+                $($decl)*
+                $($extra)*
+            ]
+            $($token)*
+        }
+    };
+}
+
+/// Concats several [`parse`](parse) calls results together.
+///
+/// Lifetimes are placed before the other parameters, and parameters with a default value
+/// (as found in the declaration form of [`parse_raw`](parse_raw)) are placed last, as rustc
+/// requires. The generics without constraints are rebuilt from the concatenated generics, so
+/// they always follow the same order. Attributes in front of a parameter, as kept by
+/// [`parse`](parse), stay in front of it.
+///
+/// With `dedup` right after the callback, parameters declared in several lists are merged
+/// into one: `concat! { cb {} dedup [<T>] [<T>] [], [<T: Clone>] [<T>] [] }` gives `<T: Clone>`
+/// instead of `<T, T: Clone>`. The bounds of the merged parameter are joined with `+`, the
+/// first default found is kept, and the attributes of every declaration are kept. A name used for parameters of different kinds, such as
+/// `const N` and `N`, is reported with a `compile_error!`. Where clauses are concatenated as
+/// usual. The names are compared by a helper `macro_rules!` defined next to the expansion, so
+/// `dedup` can only be used where items are allowed.
+///
+/// ```compile_fail
+/// macro_rules! tr {
+///     ([$($g:tt)*] [$($r:tt)*] [$($w:tt)*]) => { pub trait Tr $($g)* $($w)* { } };
+/// }
+///
+/// // generic parameter 'N' is declared both as const and as type
+/// generics2::concat! { tr {} dedup [<const N: usize>] [<N>] [], [<N>] [<N>] [] }
+/// ```
+///
+/// With `validate` right after the callback (or after `dedup`), the lifetimes used in bounds
+/// and in the where clause are checked against the concatenated list, and an undeclared one,
+/// as in `T: 'x`, is reported with a `compile_error!` at the call site instead of rustc's E0261
+/// inside the expansion. `'static` and lifetimes introduced by `for<...>` are always accepted,
+/// and `'_` is rejected anywhere in the generics. Types are not checked, since a macro cannot
+/// tell a type parameter from any other type in scope. As with `dedup`, the lifetimes are
+/// matched by a helper `macro_rules!`, so `validate` can only be used where items are allowed.
+///
+/// ```compile_fail
+/// # macro_rules! tr {
+/// #     ([$($g:tt)*] [$($r:tt)*] [$($w:tt)*]) => { pub trait Tr $($g)* $($w)* { } };
+/// # }
+/// // use of undeclared lifetime 'x
+/// generics2::concat! { tr {} validate [<T: 'x>] [<T>] [] }
+/// ```
+///
+/// ```compile_fail
+/// # macro_rules! tr {
+/// #     ([$($g:tt)*] [$($r:tt)*] [$($w:tt)*]) => { pub trait Tr $($g)* $($w)* { } };
+/// # }
+/// // anonymous lifetime '_ is not allowed in generics
+/// generics2::concat! { tr {} validate [<'_, T>] [<'_, T>] [] }
+/// ```
+///
+/// A list that is not enclosed in `<...>` is reported as invalid generics:
+///
+/// ```compile_fail
+/// macro_rules! tr {
+///     ([$($g:tt)*] [$($r:tt)*] [$($w:tt)*]) => { pub trait Tr $($g)* $($w)* { } };
+/// }
+///
+/// // invalid generics 'T'
+/// generics2::concat! { tr {} [T] [T] [] }
+/// ```
+#[macro_export]
+macro_rules! concat {
+    (
+        $callback:path { $($callback_args:tt)* }
+        validate
+        $($token:tt)*
+    ) => {
+        $crate::concat! {
+            $crate::concat_impl { @validate [$callback] [$($callback_args)*] }
+            $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        dedup validate
+        $($token:tt)*
+    ) => {
+        $crate::concat! {
+            $crate::concat_impl { @validate [$callback] [$($callback_args)*] }
+            dedup $($token)*
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        dedup
+        $($([$($g:tt)*] [$($r:tt)*] [$($w:tt)*]),+ $(,)?)?
+    ) => {
+        $crate::concat_impl! {
+            [$callback] [$($callback_args)*]
+            dedup
+            [$($([$($g)*])+)?] [$($([$($r)*])+)?] [$($([$($w)*])+)?]
+        }
+    };
+    (
+        $callback:path { $($callback_args:tt)* }
+        $($([$($g:tt)*] [$($r:tt)*] [$($w:tt)*]),+ $(,)?)?
+    ) => {
+        $crate::concat_impl! {
+            [$callback] [$($callback_args)*]
+            [$($([$($g)*])+)?] [$($([$($r)*])+)?] [$($([$($w)*])+)?]
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! concat_impl {
     (
         [$callback:path] [$($callback_args:tt)*]
+        dedup
         [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
     ) => {
         $crate::concat_g_impl! {
             @list
-            [$crate::concat_impl] [@g [$callback] [$($callback_args)*] [$($r)*] [$($w)*]]
+            [$crate::concat_impl] [@dedup [@names [@r [$callback] [$($callback_args)*] [$($w)*]]]]
             [$($g)*]
             [] []
         }
     };
     (
-        @g
-        [$callback:path] [$($callback_args:tt)*] [$($r:tt)*] [$($w:tt)*]
-        [$($g:tt)*]
+        [$callback:path] [$($callback_args:tt)*]
+        [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
     ) => {
-        $crate::concat_r_impl! {
+        $crate::concat_g_impl! {
             @list
-            [$crate::concat_impl] [@r [$callback] [$($callback_args)*] [$($g)*] [$($w)*]]
-            [$($r)*]
+            [$crate::concat_impl] [@names [@r [$callback] [$($callback_args)*] [$($w)*]]]
+            [$($g)*]
             [] []
         }
     };
     (
-        @r
-        [$callback:path] [$($callback_args:tt)*] [$($g:tt)*] [$($w:tt)*]
-        [$($r:tt)*]
+        @r
+        [$callback:path] [$($callback_args:tt)*] [$($w:tt)*]
+        [$($g:tt)*] [$($r:tt)*]
+    ) => {
+        $crate::concat_w_impl! {
+            @list
+            [$crate::concat_impl] [@w [$callback] [$($callback_args)*] [$($g)*] [$($r)*]]
+            [$($w)*]
+            []
+        }
+    };
+    (
+        @w
+        [$callback:path] [$($callback_args:tt)*] [$($g:tt)*] [$($r:tt)*]
+        [$($w:tt)*]
+    ) => {
+        $callback ! {
+            $($callback_args)*
+            [$($g)*] [$($r)*] [$($w)*]
+        }
+    };
+    (
+        @validate
+        [$callback:path] [$($callback_args:tt)*]
+        [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [[$($g)*] [$($r)*] [$($w)*]]
+            []
+            [$($g)* $($w)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [for < $($binder:lifetime),* > $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)* $($binder)*]
+            [$($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [($($inner:tt)*) $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [[$($inner:tt)*] $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [{$($inner:tt)*} $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt for < $($binder:lifetime),* > $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)* $($binder)*]
+            [$($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt ($($inner:tt)*) $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt [$($inner:tt)*] $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt {$($inner:tt)*} $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt $x1:tt for < $($binder:lifetime),* > $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)* $($binder)*]
+            [$($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt $x1:tt ($($inner:tt)*) $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt $x1:tt [$($inner:tt)*] $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt $x1:tt {$($inner:tt)*} $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt $x1:tt $x2:tt for < $($binder:lifetime),* > $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)* $($binder)*]
+            [$($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt $x1:tt $x2:tt ($($inner:tt)*) $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt $x1:tt $x2:tt [$($inner:tt)*] $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt $x1:tt $x2:tt {$($inner:tt)*} $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($inner)* $($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [$($parsed:tt)*]
+        [$($binders:tt)*]
+        [$x0:tt $x1:tt $x2:tt $x3:tt $($token:tt)*]
+    ) => {
+        $crate::concat_impl! {
+            @binders
+            [$callback] [$($callback_args)*] [$($parsed)*]
+            [$($binders)*]
+            [$($token)*]
+        }
+    };
+    (
+        @binders
+        [$callback:path] [$($callback_args:tt)*] [[$($g:tt)*] [$(< $($name:tt),+ >)?] [$($w:tt)*]]
+        [$($binders:tt)*]
+        [$($token:tt)*]
     ) => {
-        $crate::concat_w_impl! {
-            @list
-            [$crate::concat_impl] [@w [$callback] [$($callback_args)*] [$($g)*] [$($r)*]]
-            [$($w)*]
-            []
+        $crate::concat_impl! {
+            @check [$]
+            [$callback] [$($callback_args)*] [[$($g)*] [$(< $($name),+ >)?] [$($w)*]]
+            [$($($name)+)? $($binders)*]
         }
     };
     (
-        @w
-        [$callback:path] [$($callback_args:tt)*] [$($g:tt)*] [$($r:tt)*]
-        [$($w:tt)*]
+        @check [$d:tt]
+        [$callback:path] [$($callback_args:tt)*] [[$($g:tt)*] [$($r:tt)*] [$($w:tt)*]]
+        [$($declared:tt)*]
     ) => {
-        $callback ! {
-            $($callback_args)*
-            [$($g)*] [$($r)*] [$($w)*]
+        macro_rules! __generics2_check {
+            (
+                @check ['_ $d($token:tt)*]
+            ) => {
+                $crate::std_compile_error!("anonymous lifetime '_ is not allowed in generics");
+            };
+            (
+                @check ['static $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($token)*] }
+            };
+            $(
+                (
+                    @check [$declared $d($token:tt)*]
+                ) => {
+                    __generics2_check! { @check [$d($token)*] }
+                };
+            )*
+            (
+                @check [$d lifetime:lifetime $d($token:tt)*]
+            ) => {
+                $crate::std_compile_error!($crate::std_concat!(
+                    "use of undeclared lifetime ",
+                    $crate::std_stringify!($d lifetime)
+                ));
+            };
+            (
+                @check [($d($inner:tt)*) $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [[$d($inner:tt)*] $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [{$d($inner:tt)*} $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt '_ $d($token:tt)*]
+            ) => {
+                $crate::std_compile_error!("anonymous lifetime '_ is not allowed in generics");
+            };
+            (
+                @check [$d x0:tt 'static $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($token)*] }
+            };
+            $(
+                (
+                    @check [$d x0:tt $declared $d($token:tt)*]
+                ) => {
+                    __generics2_check! { @check [$d($token)*] }
+                };
+            )*
+            (
+                @check [$d x0:tt $d lifetime:lifetime $d($token:tt)*]
+            ) => {
+                $crate::std_compile_error!($crate::std_concat!(
+                    "use of undeclared lifetime ",
+                    $crate::std_stringify!($d lifetime)
+                ));
+            };
+            (
+                @check [$d x0:tt ($d($inner:tt)*) $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt [$d($inner:tt)*] $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt {$d($inner:tt)*} $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt $d x1:tt '_ $d($token:tt)*]
+            ) => {
+                $crate::std_compile_error!("anonymous lifetime '_ is not allowed in generics");
+            };
+            (
+                @check [$d x0:tt $d x1:tt 'static $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($token)*] }
+            };
+            $(
+                (
+                    @check [$d x0:tt $d x1:tt $declared $d($token:tt)*]
+                ) => {
+                    __generics2_check! { @check [$d($token)*] }
+                };
+            )*
+            (
+                @check [$d x0:tt $d x1:tt $d lifetime:lifetime $d($token:tt)*]
+            ) => {
+                $crate::std_compile_error!($crate::std_concat!(
+                    "use of undeclared lifetime ",
+                    $crate::std_stringify!($d lifetime)
+                ));
+            };
+            (
+                @check [$d x0:tt $d x1:tt ($d($inner:tt)*) $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt $d x1:tt [$d($inner:tt)*] $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt $d x1:tt {$d($inner:tt)*} $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt $d x1:tt $d x2:tt '_ $d($token:tt)*]
+            ) => {
+                $crate::std_compile_error!("anonymous lifetime '_ is not allowed in generics");
+            };
+            (
+                @check [$d x0:tt $d x1:tt $d x2:tt 'static $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($token)*] }
+            };
+            $(
+                (
+                    @check [$d x0:tt $d x1:tt $d x2:tt $declared $d($token:tt)*]
+                ) => {
+                    __generics2_check! { @check [$d($token)*] }
+                };
+            )*
+            (
+                @check [$d x0:tt $d x1:tt $d x2:tt $d lifetime:lifetime $d($token:tt)*]
+            ) => {
+                $crate::std_compile_error!($crate::std_concat!(
+                    "use of undeclared lifetime ",
+                    $crate::std_stringify!($d lifetime)
+                ));
+            };
+            (
+                @check [$d x0:tt $d x1:tt $d x2:tt ($d($inner:tt)*) $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt $d x1:tt $d x2:tt [$d($inner:tt)*] $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt $d x1:tt $d x2:tt {$d($inner:tt)*} $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($inner)* $d($token)*] }
+            };
+            (
+                @check [$d x0:tt $d x1:tt $d x2:tt $d x3:tt $d($token:tt)*]
+            ) => {
+                __generics2_check! { @check [$d($token)*] }
+            };
+            (
+                @check [$d($token:tt)*]
+            ) => {
+                $callback ! {
+                    $($callback_args)*
+                    [$($g)*] [$($r)*] [$($w)*]
+                }
+            };
         }
+
+        __generics2_check! { @check [$($g)* $($w)*] }
     };
 }
 
@@ -1192,15 +7871,444 @@ macro_rules! concat_g_impl {
     ) => {
         $crate::std_compile_error!($crate::std_concat!(
             "invalid generics '",
-            $crate::std_stringify!($($item:tt)*),
+            $crate::std_stringify!($($item)*),
             "'"
         ));
+        $crate::error_at_impl! { [] $($item)* }
+    };
+    (
+        @list
+        [$callback:path] [@dedup [$($callback_args:tt)*]]
+        []
+        [$($lifetimes:tt)*] [$($types:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @normalize
+            [$callback] [$($callback_args)*]
+            []
+            [$($lifetimes)* $($types)*]
+        }
     };
     (
         @list
         [$callback:path] [$($callback_args:tt)*]
         []
-        [] []
+        [$($lifetimes:tt)*] [$($types:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @sort
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [] []
+            [$($types)*]
+        }
+    };
+    (
+        @normalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($params:tt)*]
+        [[$(# [$($attr:tt)*])* $lifetime:lifetime $(: $($constraint:tt)*)?] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @normalize
+            [$callback] [$($callback_args)*]
+            [$($params)* [lifetime $lifetime [$($($constraint)*)?] [] [$(# [$($attr)*])*]]]
+            [$($tail)*]
+        }
+    };
+    (
+        @normalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($params:tt)*]
+        [[= [$(# [$($attr:tt)*])* const $name:ident : $($ty:tt)*] = $($default:tt)*] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @normalize
+            [$callback] [$($callback_args)*]
+            [$($params)* [const $name [$($ty)*] [$($default)*] [$(# [$($attr)*])*]]]
+            [$($tail)*]
+        }
+    };
+    (
+        @normalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($params:tt)*]
+        [[= [$(# [$($attr:tt)*])* $name:ident $(: $($constraint:tt)*)?] = $($default:tt)*] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @normalize
+            [$callback] [$($callback_args)*]
+            [$($params)* [type $name [$($($constraint)*)?] [$($default)*] [$(# [$($attr)*])*]]]
+            [$($tail)*]
+        }
+    };
+    (
+        @normalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($params:tt)*]
+        [[$(# [$($attr:tt)*])* const $name:ident : $($ty:tt)*] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @normalize
+            [$callback] [$($callback_args)*]
+            [$($params)* [const $name [$($ty)*] [] [$(# [$($attr)*])*]]]
+            [$($tail)*]
+        }
+    };
+    (
+        @normalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($params:tt)*]
+        [[$(# [$($attr:tt)*])* $name:ident $(: $($constraint:tt)*)?] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @normalize
+            [$callback] [$($callback_args)*]
+            [$($params)* [type $name [$($($constraint)*)?] [] [$(# [$($attr)*])*]]]
+            [$($tail)*]
+        }
+    };
+    (
+        @normalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$([$kind:tt $name:tt $constraint:tt $default:tt $attrs:tt])*]
+        []
+    ) => {
+        $crate::concat_g_impl! {
+            @dedup [$]
+            [$callback] [$($callback_args)*]
+            [$($name)*]
+            [$([$kind $name $constraint $default $attrs])*]
+        }
+    };
+    (
+        @dedup [$d:tt]
+        [$callback:path] [$($callback_args:tt)*]
+        [$($name:tt)*]
+        [$($entry:tt)*]
+    ) => {
+        macro_rules! __generics2_dedup {
+            (
+                @next [$d($merged:tt)*] [$d param:tt $d($params:tt)*]
+            ) => {
+                __generics2_dedup! {
+                    @find $d param [] [$d($merged)*] [$d($params)*]
+                }
+            };
+            (
+                @next [$d($merged:tt)*] []
+            ) => {
+                $crate::concat_g_impl! {
+                    @denormalize
+                    [$callback] [$($callback_args)*]
+                    [] []
+                    [$d($merged)*]
+                }
+            };
+            $(
+                (
+                    @find [$d kind:tt $name $d constraint:tt $d default:tt $d attrs:tt]
+                    [$d($before:tt)*] [[$d other_kind:tt $name $d other_constraint:tt $d other_default:tt $d other_attrs:tt] $d($after:tt)*]
+                    [$d($params:tt)*]
+                ) => {
+                    __generics2_dedup! {
+                        @merge [$name]
+                        [$d other_kind $d kind] [$d other_constraint $d constraint] [$d other_default $d default]
+                        [$d other_attrs $d attrs]
+                        [$d($before)*] [$d($after)*]
+                        [$d($params)*]
+                    }
+                };
+            )*
+            (
+                @find $d param:tt
+                [$d($before:tt)*] [$d other:tt $d($after:tt)*]
+                [$d($params:tt)*]
+            ) => {
+                __generics2_dedup! {
+                    @find $d param
+                    [$d($before)* $d other] [$d($after)*]
+                    [$d($params)*]
+                }
+            };
+            (
+                @find $d param:tt
+                [$d($merged:tt)*] []
+                [$d($params:tt)*]
+            ) => {
+                __generics2_dedup! {
+                    @next [$d($merged)* $d param] [$d($params)*]
+                }
+            };
+            (
+                @merge [$d name:tt]
+                [$d kind:ident $d other_kind:ident] [$d constraint:tt $d other_constraint:tt] [[] [$d($default:tt)+]]
+                $d attrs:tt
+                [$d($before:tt)*] [$d($after:tt)*]
+                [$d($params:tt)*]
+            ) => {
+                __generics2_dedup! {
+                    @merge [$d name]
+                    [$d kind $d other_kind] [$d constraint $d other_constraint] [[$d($default)+] []]
+                    $d attrs
+                    [$d($before)*] [$d($after)*]
+                    [$d($params)*]
+                }
+            };
+            (
+                @merge [$d name:tt]
+                [const const] [$d ty:tt $d other_ty:tt] [$d default:tt $d other_default:tt]
+                [[$d($attr:tt)*] [$d($other_attr:tt)*]]
+                [$d($before:tt)*] [$d($after:tt)*]
+                [$d($params:tt)*]
+            ) => {
+                __generics2_dedup! {
+                    @next [$d($before)* [const $d name $d ty $d default [$d($attr)* $d($other_attr)*]] $d($after)*] [$d($params)*]
+                }
+            };
+            (
+                @merge [$d name:tt]
+                [$d kind:ident $d other_kind:ident] [[] [$d($constraint:tt)+]] [$d default:tt $d other_default:tt]
+                $d attrs:tt
+                [$d($before:tt)*] [$d($after:tt)*]
+                [$d($params:tt)*]
+            ) => {
+                __generics2_dedup! {
+                    @merge [$d name]
+                    [$d kind $d other_kind] [[$d($constraint)+] []] [$d default $d other_default]
+                    $d attrs
+                    [$d($before)*] [$d($after)*]
+                    [$d($params)*]
+                }
+            };
+            (
+                @merge [$d name:tt]
+                [$d kind:ident $d other_kind:ident] [[$d($constraint:tt)+] [$d($other_constraint:tt)+]] [$d default:tt $d other_default:tt]
+                $d attrs:tt
+                [$d($before:tt)*] [$d($after:tt)*]
+                [$d($params:tt)*]
+            ) => {
+                __generics2_dedup! {
+                    @merge [$d name]
+                    [$d kind $d other_kind] [[$d($constraint)+ + $d($other_constraint)+] []] [$d default $d other_default]
+                    $d attrs
+                    [$d($before)*] [$d($after)*]
+                    [$d($params)*]
+                }
+            };
+            (
+                @merge [$d name:tt]
+                [lifetime lifetime] [$d constraint:tt []] [$d default:tt $d other_default:tt]
+                [[$d($attr:tt)*] [$d($other_attr:tt)*]]
+                [$d($before:tt)*] [$d($after:tt)*]
+                [$d($params:tt)*]
+            ) => {
+                __generics2_dedup! {
+                    @next [$d($before)* [lifetime $d name $d constraint $d default [$d($attr)* $d($other_attr)*]] $d($after)*] [$d($params)*]
+                }
+            };
+            (
+                @merge [$d name:tt]
+                [type type] [$d constraint:tt []] [$d default:tt $d other_default:tt]
+                [[$d($attr:tt)*] [$d($other_attr:tt)*]]
+                [$d($before:tt)*] [$d($after:tt)*]
+                [$d($params:tt)*]
+            ) => {
+                __generics2_dedup! {
+                    @next [$d($before)* [type $d name $d constraint $d default [$d($attr)* $d($other_attr)*]] $d($after)*] [$d($params)*]
+                }
+            };
+            (
+                @merge [$d name:tt]
+                [$d kind:ident $d other_kind:ident] [$d constraint:tt $d other_constraint:tt] [$d default:tt $d other_default:tt]
+                $d attrs:tt
+                [$d($before:tt)*] [$d($after:tt)*]
+                [$d($params:tt)*]
+            ) => {
+                $crate::std_compile_error!($crate::std_concat!(
+                    "generic parameter '",
+                    $crate::std_stringify!($d name),
+                    "' is declared both as ",
+                    $crate::std_stringify!($d kind),
+                    " and as ",
+                    $crate::std_stringify!($d other_kind)
+                ));
+            };
+        }
+
+        __generics2_dedup! { @next [] [$($entry)*] }
+    };
+    (
+        @denormalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [[lifetime $lifetime:tt [] [] [$($attr:tt)*]] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @denormalize
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)* [$($attr)* $lifetime]] [$($types)*]
+            [$($tail)*]
+        }
+    };
+    (
+        @denormalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [[lifetime $lifetime:tt [$($constraint:tt)+] [] [$($attr:tt)*]] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @denormalize
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)* [$($attr)* $lifetime : $($constraint)+]] [$($types)*]
+            [$($tail)*]
+        }
+    };
+    (
+        @denormalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [[const $name:tt [$($ty:tt)*] [] [$($attr:tt)*]] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @denormalize
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [$($types)* [$($attr)* const $name : $($ty)*]]
+            [$($tail)*]
+        }
+    };
+    (
+        @denormalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [[const $name:tt [$($ty:tt)*] [$($default:tt)+] [$($attr:tt)*]] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @denormalize
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [$($types)* [= [$($attr)* const $name : $($ty)*] = $($default)+]]
+            [$($tail)*]
+        }
+    };
+    (
+        @denormalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [[type $name:tt [] [] [$($attr:tt)*]] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @denormalize
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [$($types)* [$($attr)* $name]]
+            [$($tail)*]
+        }
+    };
+    (
+        @denormalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [[type $name:tt [$($constraint:tt)+] [] [$($attr:tt)*]] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @denormalize
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [$($types)* [$($attr)* $name : $($constraint)+]]
+            [$($tail)*]
+        }
+    };
+    (
+        @denormalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [[type $name:tt [] [$($default:tt)+] [$($attr:tt)*]] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @denormalize
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [$($types)* [= [$($attr)* $name] = $($default)+]]
+            [$($tail)*]
+        }
+    };
+    (
+        @denormalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [[type $name:tt [$($constraint:tt)+] [$($default:tt)+] [$($attr:tt)*]] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @denormalize
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [$($types)* [= [$($attr)* $name : $($constraint)+] = $($default)+]]
+            [$($tail)*]
+        }
+    };
+    (
+        @denormalize
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        []
+    ) => {
+        $crate::concat_g_impl! {
+            @sort
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [] []
+            [$($types)*]
+        }
+    };
+    (
+        @sort
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*] [$($defaulted:tt)*]
+        [[= [$($param:tt)*] $($default:tt)*] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @sort
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [$($types)*] [$($defaulted)* [$($param)* $($default)*]]
+            [$($tail)*]
+        }
+    };
+    (
+        @sort
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*] [$($defaulted:tt)*]
+        [$param:tt $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @sort
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)*] [$($types)* $param] [$($defaulted)*]
+            [$($tail)*]
+        }
+    };
+    (
+        @sort
+        [$callback:path] [@names [$($callback_args:tt)*]]
+        [$($lifetimes:tt)*] [$($types:tt)*] [$($defaulted:tt)*]
+        []
+    ) => {
+        $crate::concat_g_impl! {
+            @names
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)* $($types)* $($defaulted)*] []
+            [$($lifetimes)* $($types)* $($defaulted)*]
+        }
+    };
+    (
+        @sort
+        [$callback:path] [$($callback_args:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*] [$($defaulted:tt)*]
+        []
+    ) => {
+        $crate::concat_g_impl! {
+            @sorted
+            [$callback] [$($callback_args)*]
+            [$($lifetimes)* $($types)* $($defaulted)*]
+        }
+    };
+    (
+        @sorted
+        [$callback:path] [$($callback_args:tt)*]
+        []
     ) => {
         $callback ! {
             $($callback_args)*
@@ -1208,49 +8316,103 @@ macro_rules! concat_g_impl {
         }
     };
     (
-        @list
+        @sorted
         [$callback:path] [$($callback_args:tt)*]
-        []
-        [$([$($lifetime:tt)*])+] []
+        [$([$($param:tt)*])+]
     ) => {
         $callback ! {
             $($callback_args)*
-            [ < $($($lifetime)*),+ > ]
+            [ < $($($param)*),+ > ]
         }
     };
     (
-        @list
+        @names
+        [$callback:path] [$($callback_args:tt)*]
+        [$($params:tt)*] [$($names:tt)*]
+        [[$(# [$($attr:tt)*])* $lifetime:lifetime $($constraint:tt)*] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @names
+            [$callback] [$($callback_args)*]
+            [$($params)*] [$($names)* [$lifetime]]
+            [$($tail)*]
+        }
+    };
+    (
+        @names
+        [$callback:path] [$($callback_args:tt)*]
+        [$($params:tt)*] [$($names:tt)*]
+        [[$(# [$($attr:tt)*])* const $name:ident $($constraint:tt)*] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @names
+            [$callback] [$($callback_args)*]
+            [$($params)*] [$($names)* [$name]]
+            [$($tail)*]
+        }
+    };
+    (
+        @names
+        [$callback:path] [$($callback_args:tt)*]
+        [$($params:tt)*] [$($names:tt)*]
+        [[$(# [$($attr:tt)*])* $name:ident $($constraint:tt)*] $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @names
+            [$callback] [$($callback_args)*]
+            [$($params)*] [$($names)* [$name]]
+            [$($tail)*]
+        }
+    };
+    (
+        @names
         [$callback:path] [$($callback_args:tt)*]
+        [] []
         []
-        [] [$([$($ty:tt)*])+]
     ) => {
         $callback ! {
             $($callback_args)*
-            [ < $($($ty)*),+ > ]
+            [] []
         }
     };
     (
-        @list
+        @names
         [$callback:path] [$($callback_args:tt)*]
+        [$([$($param:tt)*])+] [$([$($name:tt)*])+]
         []
-        [$([$($lifetime:tt)*])+] [$([$($ty:tt)*])+]
     ) => {
         $callback ! {
             $($callback_args)*
-            [ < $($($lifetime)*),+ , $($($ty)*),+ > ]
+            [ < $($($param)*),+ > ]
+            [ < $($($name)*),+ > ]
+        }
+    };
+    (
+        @item
+        [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [$(# [$($attr:tt)*])* $lifetime:lifetime $($constraint:tt)*]
+        [, $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @item
+            [$callback] [$($callback_args)*] [$($list)*]
+            [$($lifetimes)* [$(# [$($attr)*])* $lifetime $($constraint)*]] [$($types)*]
+            []
+            [$($tail)*]
         }
     };
     (
         @item
         [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
         [$($lifetimes:tt)*] [$($types:tt)*]
-        [$lifetime:lifetime $($constraint:tt)*]
+        [$(# [$($attr:tt)*])* $ty:ident $($constraint:tt)*]
         [, $($tail:tt)*]
     ) => {
         $crate::concat_g_impl! {
             @item
             [$callback] [$($callback_args)*] [$($list)*]
-            [$($lifetimes)* [$lifetime $($constraint)*]] [$($types)*]
+            [$($lifetimes)*] [$($types)* [$(# [$($attr)*])* $ty $($constraint)*]]
             []
             [$($tail)*]
         }
@@ -1259,17 +8421,47 @@ macro_rules! concat_g_impl {
         @item
         [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
         [$($lifetimes:tt)*] [$($types:tt)*]
-        [$ty:ident $($constraint:tt)*]
+        [= $($param:tt)*]
         [, $($tail:tt)*]
     ) => {
         $crate::concat_g_impl! {
             @item
             [$callback] [$($callback_args)*] [$($list)*]
-            [$($lifetimes)*] [$($types)* [$ty $($constraint)*]]
+            [$($lifetimes)*] [$($types)* [= $($param)*]]
             []
             [$($tail)*]
         }
     };
+    (
+        @item
+        [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [$(# [$($attr:tt)*])* $ty:ident $($constraint:tt)*]
+        [= $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @item
+            [$callback] [$($callback_args)*] [$($list)*]
+            [$($lifetimes)*] [$($types)*]
+            [= [$(# [$($attr)*])* $ty $($constraint)*] =]
+            [$($tail)*]
+        }
+    };
+    (
+        @item
+        [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [$($param:tt)*]
+        [ << $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @item
+            [$callback] [$($callback_args)*] [$($list)*]
+            [$($lifetimes)*] [$($types)*]
+            [$($param)*]
+            [ < < $($tail)*]
+        }
+    };
     (
         @item
         [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
@@ -1290,28 +8482,42 @@ macro_rules! concat_g_impl {
         @item
         [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
         [$($lifetimes:tt)*] [$($types:tt)*]
-        [$lifetime:lifetime $($constraint:tt)*]
+        [$(# [$($attr:tt)*])* $lifetime:lifetime $($constraint:tt)*]
+        [ > ]
+    ) => {
+        $crate::concat_g_impl! {
+            @list
+            [$callback] [$($callback_args)*]
+            [$($list)*]
+            [$($lifetimes)* [$(# [$($attr)*])* $lifetime $($constraint)*]] [$($types)*]
+        }
+    };
+    (
+        @item
+        [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*]
+        [$(# [$($attr:tt)*])* $ty:ident $($constraint:tt)*]
         [ > ]
     ) => {
         $crate::concat_g_impl! {
             @list
             [$callback] [$($callback_args)*]
             [$($list)*]
-            [$($lifetimes)* [$lifetime $($constraint)*]] [$($types)*]
+            [$($lifetimes)*] [$($types)* [$(# [$($attr)*])* $ty $($constraint)*]]
         }
     };
     (
         @item
         [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
         [$($lifetimes:tt)*] [$($types:tt)*]
-        [$ty:ident $($constraint:tt)*]
+        [= $($param:tt)*]
         [ > ]
     ) => {
         $crate::concat_g_impl! {
             @list
             [$callback] [$($callback_args)*]
             [$($list)*]
-            [$($lifetimes)*] [$($types)* [$ty $($constraint)*]]
+            [$($lifetimes)*] [$($types)* [= $($param)*]]
         }
     };
     (
@@ -1337,9 +8543,10 @@ macro_rules! concat_g_impl {
     ) => {
         $crate::std_compile_error!($crate::std_concat!(
             "invalid generics '",
-            $crate::std_stringify!($($tail:tt)+),
+            $crate::std_stringify!($($tail)*),
             "'"
         ));
+        $crate::error_at_impl! { [] $($tail)* }
     };
     (
         @item
@@ -1364,6 +8571,41 @@ macro_rules! concat_g_impl {
         []
     ) => {
         $crate::std_compile_error!("invalid generics");
+        $crate::error_at_impl! { [] $($param)* }
+    };
+    (
+        @angles
+        [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*] [$($param:tt)*]
+        [$($outer_levels:tt)*]
+        [$($content:tt)*]
+        [ << $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @angles
+            [$callback] [$($callback_args)*] [$($list)*]
+            [$($lifetimes)*] [$($types)*] [$($param)*]
+            [$($outer_levels)*]
+            [$($content)*]
+            [ < < $($tail)*]
+        }
+    };
+    (
+        @angles
+        [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
+        [$($lifetimes:tt)*] [$($types:tt)*] [$($param:tt)*]
+        [$($outer_levels:tt)*]
+        [$($content:tt)*]
+        [ >> $($tail:tt)*]
+    ) => {
+        $crate::concat_g_impl! {
+            @angles
+            [$callback] [$($callback_args)*] [$($list)*]
+            [$($lifetimes)*] [$($types)*] [$($param)*]
+            [$($outer_levels)*]
+            [$($content)*]
+            [ > > $($tail)*]
+        }
     };
     (
         @angles
@@ -1441,6 +8683,7 @@ macro_rules! concat_g_impl {
         []
     ) => {
         $crate::std_compile_error!("invalid generics");
+        $crate::error_at_impl! { [] $($param)* }
     };
 }
 
@@ -1480,6 +8723,7 @@ macro_rules! concat_r_impl {
         [$($lifetimes:tt)*] [$($types:tt)*]
     ) => {
         $crate::std_compile_error!("invalid generics");
+        $crate::error_at_impl! { [] $($item)* }
     };
     (
         @list
@@ -1572,9 +8816,10 @@ macro_rules! concat_r_impl {
     ) => {
         $crate::std_compile_error!($crate::std_concat!(
             "invalid generics '",
-            $crate::std_stringify!($($tail:tt)+),
+            $crate::std_stringify!($($tail)*),
             "'"
         ));
+        $crate::error_at_impl! { [] $($tail)* }
     };
 }
 
@@ -1613,11 +8858,9 @@ macro_rules! concat_w_impl {
         [[ where $($item:tt)* ] $($list:tt)*]
         [$($w:tt)*]
     ) => {
-        $crate::concat_w_impl! {
-            @item
-            [$callback] [$($callback_args)*] [$($list)*]
-            [$($w)*]
-            []
+        $crate::where_predicates_impl! {
+            [$crate::concat_w_impl]
+            [@predicates [$callback] [$($callback_args)*] [$($list)*] [$($w)*]]
             [$($item)*]
         }
     };
@@ -1629,9 +8872,10 @@ macro_rules! concat_w_impl {
     ) => {
         $crate::std_compile_error!($crate::std_concat!(
             "invalid generics '",
-            $crate::std_stringify!($($item:tt)+),
+            $crate::std_stringify!($($item)*),
             "'"
         ));
+        $crate::error_at_impl! { [] $($item)* }
     };
     (
         @list
@@ -1645,130 +8889,610 @@ macro_rules! concat_w_impl {
         }
     };
     (
-        @item
+        @predicates
         [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
         [$($w:tt)*]
-        [$($item:tt)*]
-        [$(,)?]
+        [$([[$($ty:tt)*] [$($bounds:tt)*]])*]
+        [$($relaxed:tt)*]
+        []
     ) => {
         $crate::concat_w_impl! {
             @list
             [$callback] [$($callback_args)*]
             [$($list)*]
-            [$($w)* [$($item)*]]
+            [$($w)* $([$($ty)* : $($bounds)*])*]
         }
     };
-    (
-        @item
-        [$callback:path] [$($callback_args:tt)*] [$($list:tt)*]
-        [$($w:tt)*]
-        [$($item:tt)*]
-        [$token:tt $($tail:tt)*]
-    ) => {
-        $crate::concat_w_impl! {
-            @item
-            [$callback] [$($callback_args)*] [$($list)*]
-            [$($w)*]
-            [$($item)* $token]
-            [$($tail)*]
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::boxed::Box;
+
+    macro_rules! tokens_of {
+        (
+            @raw $const:ident [$($slot:tt)*] $($rest:tt)*
+        ) => {
+            const $const: &[&str] = &[$(stringify!($slot),)* stringify!($($rest)*)];
+        };
+        (
+            $const:ident $($token:tt)*
+        ) => {
+            const $const: &str = stringify!($($token)*);
+        };
+    }
+
+    macro_rules! test_struct {
+        (
+            $name:ident ($($field:ty),*) [$($g:tt)*] [$($r:tt)*] $([$($w:tt)*])?
+        ) => {
+            struct $name $($g)* ($($field),*) $($($w)*)?;
+        };
+    }
+
+    macro_rules! impl_test_trait {
+        (
+            struct $name:ident $($token:tt)*
+        ) => {
+            parse! {
+                impl_test_trait {
+                    @impl struct $name
+                }
+                $($token)*
+            }
+        };
+        (
+            @impl struct $name:ident [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] $($body:tt)*
+        ) => {
+            impl $($g)* TestTrait for $name $($r)* $($w)* { }
+        };
+    }
+
+    trait TestTrait { }
+
+    struct TestStruct { }
+
+    impl_test_trait! {
+        struct TestStruct { }
+    }
+
+    struct TestGenericStruct<'a, T: 'static> {
+        a: &'a (),
+        t: T,
+    }
+
+    impl_test_trait! {
+        struct TestGenericStruct<'a, T: 'static> { }
+    }
+
+    struct TestGenericStructWithDefaultParameter<T=()>(T);
+
+    impl_test_trait! {
+        struct TestGenericStructWithDefaultParameter<T=()>(T);
+    }
+
+    struct TestGenericStructWithConstrainedDefaultParameter<T: 'static = ()>(T);
+
+    impl_test_trait! {
+        struct TestGenericStructWithConstrainedDefaultParameter<T: 'static = ()>(T);
+    }
+
+    #[test]
+    fn it_works() {
+        let test_struct = TestStruct { };
+        let _: &dyn TestTrait = &test_struct;
+        let _: &dyn TestTrait2 = &test_struct;
+        let test_generic_struct = TestGenericStruct {
+            a: &(),
+            t: ()
+        };
+        let _ = test_generic_struct.a;
+        let () = test_generic_struct.t;
+        let _: &dyn TestTrait = &test_generic_struct;
+        let _: &dyn TestTrait2 = &test_generic_struct;
+        let test_generic_struct_ = TestGenericStructWithDefaultParameter(());
+        let _: &dyn TestTrait = &test_generic_struct_;
+        let () = test_generic_struct_.0;
+        let test_generic_struct__ = TestGenericStructWithConstrainedDefaultParameter(());
+        let _: &dyn TestTrait = &test_generic_struct__;
+        let () = test_generic_struct__.0;
+    }
+
+    macro_rules! impl_tr {
+        (
+            struct $name:ident $($token:tt)*
+        ) => {
+            parse! {
+                impl_tr {
+                    @impl struct $name
+                }
+                $($token)*
+            }
+        };
+        (
+            @impl struct $name:ident [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] become $tr:ident $($body:tt)*
+        ) => {
+            impl $($g)* $tr for $name $($r)* $($w)* { }
+        };
+    }
+
+    trait TestTrait2 { }
+
+    impl_tr! {
+        struct TestStruct become TestTrait2 { }
+    }
+
+    impl_tr! {
+        struct TestGenericStruct<'a, T> become TestTrait2 where T: 'static { }
+    }
+
+    struct TestHrtbStruct<T, F: for<'a> Fn(&'a T) -> Box<dyn for<'b> Fn(&'b T) -> Option<&'b T>>>(T, F);
+
+    impl_test_trait! {
+        struct TestHrtbStruct<T, F: for<'a> Fn(&'a T) -> Box<dyn for<'b> Fn(&'b T) -> Option<&'b T>>>(T, F);
+    }
+
+    struct TestHrtbWhereStruct<T, F>(T, F);
+
+    impl_tr! {
+        struct TestHrtbWhereStruct<T, F> become TestTrait2 where
+            for<'a> &'a T: IntoIterator<Item = &'a u8>,
+            F: for<'c> Fn(&'c T) -> Option<Result<&'c T, ()>>,
+        { }
+    }
+
+    macro_rules! struct_C {
+        (
+            @concat [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] $($body:tt)*
+        ) => {
+            concat! {
+                struct_C { @struct }
+                [<'x>] [<'x>] [],
+                [$($g)*] [$($r)*] [$($w)*]
+            }
+        };
+        (
+            @struct [$($g:tt)*] [$($r:tt)*] [$($w:tt)*]
+        ) => {
+            struct C $($g)* $($w)* {
+                x: &'x (),
+                t: T,
+                f: F,
+            }
+        };
+        (
+            $($token:tt)*
+        ) => {
+            parse! { struct_C { @concat } $($token)* }
+        };
+    }
+
+    struct_C! {
+        <T, F: for<'a> Fn(&'a T) -> Option<Box<dyn for<'b> Fn(&'b u8) -> &'b u8>>>
+        where for<'a> &'a T: IntoIterator;
+    }
+
+    fn some_ref(x: &u8) -> Option<&u8> { Some(x) }
+
+    type SomeRef = Box<dyn for<'b> Fn(&'b u8) -> Option<&'b u8>>;
+
+    fn ok_ref<T>(t: &T) -> Option<Result<&T, ()>> { Some(Ok(t)) }
+
+    #[test]
+    fn run_hrtb() {
+        let s = TestHrtbStruct(1u8, |_: &u8| -> SomeRef { Box::new(some_ref) });
+        let _: &dyn TestTrait = &s;
+        assert_eq!((s.1)(&s.0)(&2), Some(&2));
+        let s = TestHrtbWhereStruct([1u8], ok_ref::<[u8; 1]>);
+        let _: &dyn TestTrait2 = &s;
+        assert_eq!((s.1)(&s.0), Some(Ok(&[1])));
+        let c = C { x: &(), t: [1u8], f: |_: &[u8; 1]| None };
+        let _ = c.x;
+        assert!((c.f)(&c.t).is_none());
+        assert_eq!(c.t.iter().count(), 1);
+    }
+
+    trait TestHeader {
+        const HEADER: &'static str;
+    }
+
+    macro_rules! impl_test_header {
+        (
+            @impl [$($attr:tt)*] [$($vis:tt)*] [$keyword:tt] [$name:ident] [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] $($body:tt)*
+        ) => {
+            impl $($g)* TestHeader for $name $($r)* $($w)* {
+                const HEADER: &'static str = stringify!($($attr)* [$($vis)*] $keyword $name);
+            }
+        };
+        (
+            $($token:tt)*
+        ) => {
+            parse_item! { impl_test_header { @impl } $($token)* }
+        };
+    }
+
+    #[derive(Clone)]
+    pub(crate) struct TestItemStruct<'a, T> where T: 'a {
+        t: &'a T,
+    }
+
+    impl_test_header! {
+        #[derive(Clone)]
+        pub(crate) struct TestItemStruct<'a, T> where T: 'a {
+            t: &'a T,
+        }
+    }
+
+    #[allow(dead_code)]
+    enum TestItemEnum<T: Copy = u8> {
+        A(T),
+    }
+
+    impl_test_header! {
+        #[allow(dead_code)]
+        enum TestItemEnum<T: Copy = u8> {
+            A(T),
+        }
+    }
+
+    pub union TestItemUnion<T: Copy> {
+        t: T,
+    }
+
+    impl_test_header! {
+        pub union TestItemUnion<T: Copy> {
+            t: T,
+        }
+    }
+
+    type TestItemAlias = TestStruct;
+
+    impl_test_header! {
+        type TestItemAlias = TestStruct;
+    }
+
+    parse_item! { tokens_of { ITEM_ALIAS_WHERE } type Alias<T> where T: Copy = Vec<T>; }
+
+    #[test]
+    fn run_parse_item() {
+        let t = TestItemStruct { t: &0u8 };
+        let _ = t.clone().t;
+        let _ = TestItemEnum::A(0u8);
+        let _ = unsafe { TestItemUnion { t: 0u8 }.t };
+        assert_tokens_eq(
+            &[
+                <TestItemStruct<u8>>::HEADER,
+                <TestItemEnum>::HEADER,
+                <TestItemUnion<u8>>::HEADER,
+                <TestItemAlias>::HEADER,
+            ],
+            &[
+                stringify!(#[derive(Clone)] [pub(crate)] struct TestItemStruct),
+                stringify!(#[allow(dead_code)] [] enum TestItemEnum),
+                stringify!([pub] union TestItemUnion),
+                stringify!([] type TestItemAlias),
+            ]
+        );
+        assert_tokens_eq(
+            &[ITEM_ALIAS_WHERE],
+            &[stringify!([] [] [type] [Alias] [<T>] [<T>] [where T: Copy] = Vec<T>;)]
+        );
+    }
+
+    parse_impl! {
+        tokens_of { IMPL_TRAIT }
+        unsafe impl<'a, T: Clone> Tr<'a, Vec<T>> for for<'b> fn(&'b T) -> Option<T> where T: 'a { }
+    }
+
+    parse_impl! { tokens_of { IMPL_NEGATIVE } impl<T> !Send for Wrapper<T> { } }
+
+    parse_impl! { tokens_of { IMPL_INHERENT } impl Wrapper<Vec<u8>> { fn f() { } } }
+
+    #[test]
+    fn run_parse_impl() {
+        assert_tokens_eq(
+            &[IMPL_TRAIT, IMPL_NEGATIVE, IMPL_INHERENT],
+            &[
+                stringify!(
+                    [unsafe] [<'a, T: Clone>] [<'a, T>] [where T: 'a]
+                    [] [Tr<'a, Vec<T>>] [for<'b> fn(&'b T) -> Option<T>] { }
+                ),
+                stringify!([] [<T>] [<T>] [] [!] [Send] [Wrapper<T>] { }),
+                stringify!([] [] [] [] [] [] [Wrapper<Vec<u8>>] { fn f() { } }),
+            ]
+        );
+    }
+
+    parse_struct! {
+        tokens_of { FIELDS_NAMED }
+        pub struct S<T> where T: Copy { #[doc = "a"] a: T, pub(in crate::tests) b: Vec<T>, } rest
+    }
+
+    parse_struct! { tokens_of { FIELDS_TUPLE } struct P<T>(pub T, Option<fn(T) -> T>) where T: Copy; rest }
+
+    parse_struct! { tokens_of { FIELDS_UNIT } struct U; rest }
+
+    #[test]
+    fn run_parse_struct() {
+        assert_tokens_eq(
+            &[FIELDS_NAMED, FIELDS_TUPLE, FIELDS_UNIT],
+            &[
+                stringify!(
+                    [] [pub] [S] [<T>] [<T>] [where T: Copy] [struct]
+                    [[a [#[doc = "a"]] [] [T]] [b [] [pub(in crate::tests)] [Vec<T>]]] rest
+                ),
+                stringify!(
+                    [] [] [P] [<T>] [<T>] [where T: Copy] [tuple]
+                    [[0 [] [pub] [T]] [1 [] [] [Option<fn(T) -> T>]]] rest
+                ),
+                stringify!([] [] [U] [] [] [] [unit] [] rest),
+            ]
+        );
+    }
+
+    parse_enum! {
+        tokens_of { VARIANTS }
+        #[repr(u8)] pub enum E<'a, T: 'a> where T: Clone {
+            #[default] A,
+            B(&'a T, pub(crate) Vec<(T, u8)>) = 2,
+            C { #[doc = "x"] x: HashMap<u8, T>, pub y: u8 },
         }
-    };
-}
+        rest
+    }
 
-#[cfg(test)]
-mod tests {
-    macro_rules! impl_test_trait {
-        (
-            struct $name:ident $($token:tt)*
-        ) => {
-            parse! {
-                impl_test_trait {
-                    @impl struct $name
-                }
-                $($token)*
-            }
-        };
-        (
-            @impl struct $name:ident [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] $($body:tt)*
-        ) => {
-            impl $($g)* TestTrait for $name $($r)* $($w)* { }
-        };
+    #[test]
+    fn run_parse_enum() {
+        assert_tokens_eq(&[VARIANTS], &[stringify!(
+            [#[repr(u8)]] [pub] [E] [<'a, T: 'a>] [<'a, T>] [where T: Clone]
+            [
+                [unit A [#[default]] [] []]
+                [tuple B [] [[0 [] [] [&'a T]] [1 [] [pub(crate)] [Vec<(T, u8)>]]] [2]]
+                [struct C [] [[x [#[doc = "x"]] [] [HashMap<u8, T>]] [y [] [pub] [u8]]] []]
+            ]
+            rest
+        )]);
     }
 
-    trait TestTrait { }
+    parse_trait! {
+        tokens_of { TRAIT_SUPERTRAITS }
+        pub unsafe trait Name<'a, T = u8>: Super1 + Super2<Vec<T>> + 'a + for<'b> Fn(&'b T) -> u8 where Self: Sized { }
+    }
 
-    struct TestStruct { }
+    parse_trait! { tokens_of { TRAIT_AUTO } auto trait Plain { } }
 
-    impl_test_trait! {
-        struct TestStruct { }
+    #[test]
+    fn run_parse_trait() {
+        assert_tokens_eq(
+            &[TRAIT_SUPERTRAITS, TRAIT_AUTO],
+            &[
+                stringify!(
+                    [] [pub] [unsafe] [] [Name] [<'a, T:>] [<'a, T>] [where Self: Sized]
+                    [[Super1] [Super2<Vec<T>>] ['a] [for<'b> Fn(&'b T) -> u8]]
+                    [<'a, T:, __Self: Name<'a, T>>] { }
+                ),
+                stringify!([] [] [] [auto] [Plain] [] [] [] [] [<__Self: Plain>] { }),
+            ]
+        );
     }
 
-    struct TestGenericStruct<'a, T: 'static> {
-        a: &'a (),
-        t: T,
+    parse_fn! {
+        tokens_of { FN_METHOD }
+        #[inline] pub(crate) const unsafe extern "C" fn get<K, V: Clone>(&self, map: HashMap<K, V>, (a, b): (u8, u8),) -> Option<Vec<V>> where K: Fn() -> Vec<u8> { None }
     }
 
-    impl_test_trait! {
-        struct TestGenericStruct<'a, T: 'static> { }
+    parse_fn! { tokens_of { FN_DECL } async fn f<'a>(mut x: &'a u8); }
+
+    #[test]
+    fn run_parse_fn() {
+        assert_tokens_eq(
+            &[FN_METHOD, FN_DECL],
+            &[
+                stringify!(
+                    [#[inline]] [pub(crate)] [const unsafe extern "C"] [get] [<K, V: Clone>] [<K, V>]
+                    [where K: Fn() -> Vec<u8>] [[[&self] []] [[map] [HashMap<K, V>]] [[(a, b)] [(u8, u8)]]]
+                    [Option<Vec<V>>] { None }
+                ),
+                stringify!([] [] [async] [f] [<'a>] [<'a>] [] [[[mut x] [&'a u8]]] [] ;),
+            ]
+        );
     }
 
-    struct TestGenericStructWithDefaultParameter<T=()>(T);
+    parse_args! {
+        tokens_of { ARGS }
+        <'a, Vec<Option<T>>, { N + 1 }, 4, Item = <T as Tr<u8>>::Out, Error: Clone + 'a, Iter<'b> = &'b T, Ref<'b>: Copy, Vec<T>> ;
+    }
 
-    impl_test_trait! {
-        struct TestGenericStructWithDefaultParameter<T=()>(T);
+    parse_args! { tokens_of { ARGS_NESTED } <T, Vec<u8>>> ; }
+
+    parse_args! { tokens_of { NO_ARGS } ; }
+
+    #[test]
+    fn run_parse_args() {
+        assert_tokens_eq(
+            &[ARGS, ARGS_NESTED, NO_ARGS],
+            &[
+                stringify!(
+                    [
+                        [lifetime ['a]] [type [Vec<Option<T>>]] [const [{ N + 1 }]] [const [4]]
+                        [binding Item [<T as Tr<u8>>::Out]] [constraint Error [Clone + 'a]]
+                        [binding Iter<'b> [&'b T]] [constraint Ref<'b> [Copy]] [type [Vec<T>]]
+                    ] ;
+                ),
+                stringify!([[type [T]] [type [Vec<u8>]]] > ;),
+                stringify!([] ;),
+            ]
+        );
     }
 
-    struct TestGenericStructWithConstrainedDefaultParameter<T: 'static = ()>(T);
+    parse_raw! { add_bounds { tokens_of { @raw BOUNDED } [Clone] } <'a, T: ?Sized = (), const N: usize, U> ; }
 
-    impl_test_trait! {
-        struct TestGenericStructWithConstrainedDefaultParameter<T: 'static = ()>(T);
+    parse_raw! { add_bounds { tokens_of { @raw BOUNDED_WHERE } where [Clone] } <'a, T, U> where U: 'a ; }
+
+    parse_raw! { add_bounds { tokens_of { @raw BOUNDED_NONE } [Clone] } ; }
+
+    #[test]
+    fn run_add_bounds() {
+        assert_tokens_eq(
+            &[BOUNDED[0], BOUNDED[2], BOUNDED[3], BOUNDED[4], BOUNDED[11]],
+            &[
+                stringify!([[['a] [T : ?Sized + Clone] [const N : usize] [U : Clone]]]),
+                stringify!([]),
+                stringify!([[lifetime 'a [] []] [type T [?Sized + Clone] [()]] [const N [usize] []] [type U [Clone] []]]),
+                stringify!([]),
+                stringify!(;),
+            ]
+        );
+        assert_tokens_eq(
+            &[BOUNDED_WHERE[0], BOUNDED_WHERE[2], BOUNDED_WHERE[3], BOUNDED_WHERE[4]],
+            &[
+                stringify!([[['a] [T] [U]]]),
+                stringify!([where U: 'a, T: Clone, U: Clone]),
+                stringify!([[lifetime 'a [] []] [type T [] []] [type U [] []]]),
+                stringify!([[[U] ['a]] [[T] [Clone]] [[U] [Clone]]]),
+            ]
+        );
+        assert_tokens_eq(&BOUNDED_NONE[..5], &["[]", "[]", "[]", "[]", "[]"]);
     }
 
+    parse_raw! { phantom_marker { tokens_of { MARKER_COVARIANT } } <'a, T: 'a, const N: usize, U = ()> }
+
+    parse_raw! { phantom_marker { tokens_of { MARKER_INVARIANT } invariant } <'a, T> }
+
+    parse_raw! { phantom_marker { tokens_of { MARKER_CONTRAVARIANT } contravariant } <'a, T> }
+
+    parse_raw! { phantom_marker { tokens_of { MARKER_EMPTY } } }
+
+    parse_raw! { phantom_marker { tokens_of { MARKER_UNSIZED } } <T: ?Sized, U> }
+
+    parse_raw! { phantom_marker { tokens_of { MARKER_UNSIZED_WHERE } contravariant } <T> where T: ?Sized }
+
     #[test]
-    fn it_works() {
-        let test_struct = TestStruct { };
-        let _: &dyn TestTrait = &test_struct;
-        let test_generic_struct = TestGenericStruct {
-            a: &(),
-            t: ()
-        };
-        let _ = test_generic_struct.a;
-        let _ = test_generic_struct.t;
-        let _: &dyn TestTrait = &test_generic_struct;
-        let test_generic_struct_ = TestGenericStructWithDefaultParameter(());
-        let _: &dyn TestTrait = &test_generic_struct_;
-        let _ = test_generic_struct_.0;
-        let test_generic_struct__ = TestGenericStructWithConstrainedDefaultParameter(());
-        let _: &dyn TestTrait = &test_generic_struct__;
-        let _ = test_generic_struct__.0;
+    fn run_phantom_marker() {
+        assert_tokens_eq(
+            &[
+                MARKER_COVARIANT, MARKER_INVARIANT, MARKER_CONTRAVARIANT, MARKER_EMPTY,
+                MARKER_UNSIZED, MARKER_UNSIZED_WHERE,
+            ],
+            &[
+                stringify!([$crate::std_PhantomData<(&'a (), T, U,)>]),
+                stringify!([$crate::std_PhantomData<(fn(&'a ()) -> &'a (), fn(T) -> T,)>]),
+                stringify!([$crate::std_PhantomData<(fn(&'a ()), fn(T),)>]),
+                stringify!([$crate::std_PhantomData<()>]),
+                stringify!([$crate::std_PhantomData<($crate::std_PhantomData<T>, U,)>]),
+                stringify!([$crate::std_PhantomData<(fn($crate::std_PhantomData<T>),)>]),
+            ]
+        );
     }
 
-    macro_rules! impl_tr {
+    parse_raw! { filter_lifetimes { tokens_of { FILTER_LIFETIMES } } <'a: 'b, T: Clone, 'b, const N: usize = 4, U> }
+
+    parse_raw! { filter_types { tokens_of { FILTER_TYPES } } <'a: 'b, T: Clone, 'b, const N: usize = 4, U> }
+
+    parse_raw! { filter_consts { tokens_of { FILTER_CONSTS } } <'a: 'b, T: Clone, 'b, const N: usize = 4, U> }
+
+    parse_raw! { filter_consts { tokens_of { FILTER_NONE } } <'a, T> }
+
+    parse_raw! { filter_types { test_struct { FilteredTypes (T, U) } } <'a: 'b, T: Clone, 'b, const N: usize = 4, U> }
+
+    #[test]
+    fn run_filter() {
+        assert_tokens_eq(
+            &[FILTER_LIFETIMES, FILTER_TYPES, FILTER_CONSTS, FILTER_NONE],
+            &[
+                stringify!([<'a: 'b, 'b>] [<'a, 'b>]),
+                stringify!([<T: Clone, U>] [<T, U>]),
+                stringify!([<const N: usize>] [<N>]),
+                stringify!([] []),
+            ]
+        );
+        let filtered = FilteredTypes(1u8, "u");
+        assert_eq!((filtered.0, filtered.1), (1, "u"));
+    }
+
+    parse_raw! {
+        rename { tokens_of { @raw RENAMED_SUBSTITUTE } [T => __T0, 'a => 'b] }
+        <'a, T: Clone + 'a, U: Into<Vec<T>>> where T: Into<(U, [T; 1])>
+    }
+
+    parse_raw! { rename { tokens_of { @raw RENAMED_SWAP } [T => U, U => T] } <T: Into<U>, U = T> }
+
+    parse_raw! { rename { tokens_of { @raw RENAMED_HYGIENIC } hygienic } <'a, T: 'a, const N: usize, U: AsRef<[T; N]>> }
+
+    parse_raw! { rename { tokens_of { @raw RENAMED_EMPTY } hygienic } }
+
+    parse_raw! { rename { tokens_of { @raw RENAMED_POOL } hygienic [A0 A1] ['a0] } <'a, T: 'a, const N: usize> }
+
+    parse_raw! { rename { tokens_of { @raw RENAMED_RELAXED } [T => X] } <T> where T: ?Sized }
+
+    parse_raw! { rename { tokens_of { @raw RENAMED_ATTRS } [T => X] } <#[cfg(test)] T: Clone = ()> }
+
+    macro_rules! renamed_pair {
         (
-            struct $name:ident $($token:tt)*
+            @first [$($parsed:tt)*]
         ) => {
-            parse! {
-                impl_tr {
-                    @impl struct $name
-                }
-                $($token)*
-            }
+            rename! { renamed_pair { @renamed_first } hygienic [$($parsed)*] }
         };
         (
-            @impl struct $name:ident [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] become $tr:ident $($body:tt)*
+            @renamed_first [$($first:tt)*]
         ) => {
-            impl $($g)* $tr for $name $($r)* $($w)* { }
+            parse_raw! { renamed_pair { @second [$($first)*] } <T: Clone> }
+        };
+        (
+            @second [$($first:tt)*] [$($parsed:tt)*]
+        ) => {
+            rename! { renamed_pair { @renamed_second [$($first)*] } hygienic [B0] [] [$($parsed)*] }
+        };
+        (
+            @renamed_second
+            [[$([$([$($g1:tt)*])*])?] [$([$([$($r1:tt)*])*])?] [$($w1:tt)*] $($extra1:tt)*]
+            [[$([$([$($g2:tt)*])*])?] [$([$([$($r2:tt)*])*])?] [$($w2:tt)*] $($extra2:tt)*]
+        ) => {
+            concat! {
+                test_struct { RenamedPair (__G0, B0) }
+                [$(<$($($g1)*),*>)?] [$(<$($($r1)*),*>)?] [$($w1)*],
+                [$(<$($($g2)*),*>)?] [$(<$($($r2)*),*>)?] [$($w2)*]
+            }
         };
     }
 
-    trait TestTrait2 { }
-
-    impl_tr! {
-        struct TestStruct become TestTrait2 { }
-    }
+    parse_raw! { renamed_pair { @first } <T: Copy> }
 
-    impl_tr! {
-        struct TestGenericStruct<'a, T> become TestTrait2 where T: 'static { }
+    #[test]
+    fn run_rename() {
+        assert_tokens_eq(
+            &[RENAMED_SUBSTITUTE[0], RENAMED_SUBSTITUTE[1], RENAMED_SUBSTITUTE[2]],
+            &[
+                stringify!([[['b] [__T0 : Clone + 'b] [U : Into<Vec<__T0>>]]]),
+                stringify!([[['b] [__T0] [U]]]),
+                stringify!([where __T0: Into<(U, [__T0; 1])>]),
+            ]
+        );
+        assert_tokens_eq(&RENAMED_SWAP[..3], &[stringify!([[[U : Into<T>] [T :]]]), stringify!([[[U] [T]]]), "[]"]);
+        assert_tokens_eq(
+            &RENAMED_HYGIENIC[..2],
+            &[
+                stringify!([[['__g0] [__G0 : '__g0] [const __G1 : usize] [__G2 : AsRef<[__G0; __G1]>]]]),
+                stringify!([[['__g0] [__G0] [__G1] [__G2]]]),
+            ]
+        );
+        assert_tokens_eq(&RENAMED_EMPTY[..3], &["[]", "[]", "[]"]);
+        assert_tokens_eq(
+            &RENAMED_POOL[..2],
+            &[stringify!([[['a0] [A0 : 'a0] [const A1 : usize]]]), stringify!([[['a0] [A0] [A1]]])]
+        );
+        assert_tokens_eq(&[RENAMED_RELAXED[2], RENAMED_RELAXED[8]], &[stringify!([where X: ?Sized]), "[X]"]);
+        assert_tokens_eq(
+            &[RENAMED_ATTRS[0], RENAMED_ATTRS[1], RENAMED_ATTRS[5]],
+            &[
+                stringify!([[[#[cfg(test)] X : Clone]]]),
+                stringify!([[[X]]]),
+                stringify!([[[#[cfg(test)] X : Clone = ()]]]),
+            ]
+        );
+        let pair = RenamedPair(1u8, "b");
+        assert_eq!((pair.0, pair.1), (1, "b"));
     }
 
     macro_rules! struct_A {
@@ -1836,4 +9560,344 @@ mod tests {
         let _ = x.c;
         let _ = x.d;
     }
+
+    fn assert_tokens_eq(left: &[&str], right: &[&str]) {
+        let squash = |s: &&str| s.chars().filter(|c| !c.is_whitespace()).collect::<std::string::String>();
+        assert_eq!(
+            left.iter().map(squash).collect::<std::vec::Vec<_>>(),
+            right.iter().map(squash).collect::<std::vec::Vec<_>>()
+        );
+    }
+
+    parse_raw! { tokens_of { @raw PARAMS } <'a: 'b, T: Clone + 'static = (), const N: usize = 4, U> where T: Copy; }
+
+    parse_raw! { tokens_of { @raw NO_PARAMS } { } }
+
+    #[test]
+    fn run_parse_raw_params() {
+        assert_tokens_eq(&[PARAMS[3], NO_PARAMS[3]], &[
+            stringify!([[lifetime 'a ['b] []] [type T [Clone + 'static] [()]] [const N [usize] [4]] [type U [] []]]),
+            "[]",
+        ]);
+    }
+
+    parse_raw! {
+        tokens_of { @raw PREDICATES } <'a, T> where
+            T: Into<(u8, u16)> + Clone,
+            for<'b, 'c> &'b T: PartialEq<&'c T>,
+            'a: 'static,
+            <T as Iterator>::Item: From<Result<u8, ()>>,
+        { }
+    }
+
+    parse_raw! { tokens_of { @raw NO_PREDICATES } <T>; }
+
+    #[test]
+    fn run_parse_raw_predicates() {
+        assert_tokens_eq(&[PREDICATES[4], NO_PREDICATES[4]], &[
+            stringify!([
+                [[T] [Into<(u8, u16)> + Clone]]
+                [[for<'b, 'c> &'b T] [PartialEq<&'c T>]]
+                [['a] ['static]]
+                [[<T as Iterator>::Item] [From<Result<u8, ()>>]]
+            ]),
+            "[]",
+        ]);
+    }
+
+    parse_raw! { tokens_of { @raw DECL } <'a: 'b, T: Clone = Vec<u8>, const N: usize = { 2 + 2 }, U> where T: Copy; }
+
+    concat! {
+        test_struct { ConcatDefaults (&'a U, T, [u8; N]) }
+        [<T: Clone = (), const N: usize = 4>] [<T, N>] [], [<'a, U>] [<'a, U>] [where U: 'a]
+    }
+
+    #[test]
+    fn run_parse_raw_decl() {
+        assert_tokens_eq(&[DECL[5], NO_PARAMS[5]], &[
+            stringify!([[['a: 'b] [T: Clone = Vec<u8>] [const N: usize = { 2 + 2 }] [U]]]),
+            "[]",
+        ]);
+        let defaults: ConcatDefaults<u8> = ConcatDefaults(&1, (), [2; 4]);
+        assert_eq!((*defaults.0, defaults.2), (1, [2; 4]));
+    }
+
+    concat! {
+        test_struct { ConcatDedup (&'a T, &'b (), [u8; N]) }
+        dedup [<'a, T, const N: usize>] [<'a, T, N>] [], [<'b, 'a: 'b, T: Clone = ()>] [<'b, 'a, T>] []
+    }
+
+    #[test]
+    fn run_concat_dedup() {
+        let dedup: ConcatDedup<2> = ConcatDedup(&(), &(), [4; 2]);
+        let ((), ()) = (*dedup.0, *dedup.1);
+        assert_eq!(dedup.2, [4; 2]);
+    }
+
+    parse_raw! { tokens_of { @raw CONST_PARAMS } <const B: bool = { cfg!(test) }, const M: crate::Mode, N, const K: i8 = -1>; }
+
+    struct TestConstStruct<const LEN: core::primitive::usize = 4, const FLAG: bool = { cfg!(test) }>([u8; LEN]);
+
+    impl_test_trait! {
+        struct TestConstStruct<const LEN: core::primitive::usize = 4, const FLAG: bool = { cfg!(test) }>([u8; LEN]);
+    }
+
+    #[test]
+    fn run_const_params() {
+        assert_tokens_eq(&[CONST_PARAMS[3]], &[
+            stringify!([[const B [bool] [{ cfg!(test) }]] [const M [crate::Mode] []] [type N [] []] [const K [i8] [-1]]]),
+        ]);
+        let test_const_struct: TestConstStruct = TestConstStruct([0; 4]);
+        let _: &dyn TestTrait = &test_const_struct;
+        let [_, _, _, _] = test_const_struct.0;
+    }
+
+    parse_raw! { tokens_of { @raw DECL_ATTRS } <#[cfg(test)] 'a, #[doc = "T"] #[cfg(test)] T: Clone = (), U>; }
+
+    struct TestAttrsStruct<#[cfg(test)] 'a, #[cfg(test)] T: 'a>(&'a T);
+
+    impl_test_trait! {
+        struct TestAttrsStruct<#[cfg(test)] 'a, #[cfg(test)] T: 'a>(&'a T);
+    }
+
+    concat! { tokens_of { CONCAT_ATTRS } dedup [<#[cfg(test)] T: Clone, 'a>] [<T, 'a>] [], [<T: Copy>] [<T>] [] }
+
+    concat! { tokens_of { CONCAT_ATTRS_DEFAULT } [<#[cfg(test)] T = u8>] [<T>] [], [<#[cfg(test)] 'a>] [<'a>] [] }
+
+    #[test]
+    fn run_param_attrs() {
+        assert_tokens_eq(&[DECL_ATTRS[0], DECL_ATTRS[1], DECL_ATTRS[5], DECL_ATTRS[6]], &[
+            stringify!([[[#[cfg(test)] 'a] [#[doc = "T"] #[cfg(test)] T: Clone] [U]]]),
+            stringify!([[['a] [T] [U]]]),
+            stringify!([[[#[cfg(test)] 'a] [#[doc = "T"] #[cfg(test)] T: Clone = ()] [U]]]),
+            stringify!([[#[cfg(test)]] [#[doc = "T"] #[cfg(test)]] []]),
+        ]);
+        assert_tokens_eq(&[CONCAT_ATTRS, CONCAT_ATTRS_DEFAULT], &[
+            stringify!([<'a, #[cfg(test)] T: Clone + Copy>] [<'a, T>] []),
+            stringify!([<#[cfg(test)] 'a, #[cfg(test)] T = u8>] [<'a, T>] []),
+        ]);
+        let test_attrs_struct = TestAttrsStruct(&());
+        let _: &dyn TestTrait = &test_attrs_struct;
+        let () = *test_attrs_struct.0;
+    }
+
+    concat! {
+        tokens_of { CONCAT_VALIDATE }
+        validate [<'a: 'b, T: 'static>] [<'a, T>] [where T: for<'c> Fn(&'c T) -> &'b u8], [<'b>] [<'b>] []
+    }
+
+    #[test]
+    fn run_concat_validate() {
+        assert_tokens_eq(&[CONCAT_VALIDATE], &[
+            stringify!([<'a: 'b, 'b, T: 'static>] [<'a, 'b, T>] [where T: for<'c> Fn(&'c T) -> &'b u8]),
+        ]);
+    }
+
+    macro_rules! impl_fn {
+        (
+            $name:ident ($($arg:tt)*) -> $ret:ty [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] $(=>)? { $($body:tt)* }
+        ) => {
+            fn $name $($g)* ($($arg)*) -> $ret $($w)* { $($body)* }
+        };
+        (
+            $name:ident ($($arg:tt)*) -> $ret:ty
+            [[$([$([$($g:tt)*])*])?] [$($r:tt)*] [$($w:tt)*] $($extra:tt)*] { $($body:tt)* }
+        ) => {
+            fn $name $(<$($($g)*),*>)? ($($arg)*) -> $ret $($w)* { $($body)* }
+        };
+    }
+
+    parse_raw! { tokens_of { @raw SIZED } <'a, T: ?Sized, U: Clone + ?Sized, V: 'a, const LEN: usize>; }
+
+    parse_raw! { tokens_of { @raw SIZED_WHERE } <T, U, V> where U: Clone + ?Sized, T: Clone, Vec<V>: ?Sized; }
+
+    parse_raw! {
+        tokens_of { @raw SIZED_LONG } <T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11> where
+            T0: Clone + Send, T1: Clone + Send, T2: Clone + Send, T3: Clone + Send,
+            T4: Clone + Send, T5: Clone + Send, T6: Clone + Send, T7: Clone + Send,
+            T8: Clone + Send, T9: Clone + Send, T10: Clone + Send, T11: ?Sized + Send,
+            T0: Sync, T1: Sync, T2: Sync;
+    }
+
+    impl TestStruct {
+        parse! { impl_fn { by_ref (t: &T) -> &T } <T> where T: ?Sized { t } }
+
+        parse_raw! { impl_fn { by_ref_raw (t: &T) -> &T } <T> where T: ?Sized { t } }
+
+        parse! {
+            impl_fn { long (t: (T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11)) -> T0 } <T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11> where
+                T0: Clone + Send, T1: Clone + Send, T2: Clone + Send, T3: Clone + Send,
+                T4: Clone + Send, T5: Clone + Send, T6: Clone + Send, T7: Clone + Send,
+                T8: Clone + Send, T9: Clone + Send, T10: Clone + Send, T11: Clone + Send,
+                T0: Sync, T1: Sync, T2: Sync
+            { t.0 }
+        }
+    }
+
+    #[test]
+    fn run_sized() {
+        assert_tokens_eq(&[SIZED[7], SIZED[8], SIZED_WHERE[7], SIZED_WHERE[8], SIZED_LONG[7], SIZED_LONG[8]], &[
+            "[[] [unsized] [unsized] [sized] []]",
+            "[]",
+            "[[sized] [sized] [sized]]",
+            "[U]",
+            stringify!([
+                [sized] [sized] [sized] [sized] [sized] [sized]
+                [sized] [sized] [sized] [sized] [sized] [sized]
+            ]),
+            "[T11]",
+        ]);
+        assert_eq!(TestStruct::by_ref::<str>("x"), "x");
+        assert_eq!(TestStruct::by_ref_raw::<str>("y"), "y");
+        assert_eq!(TestStruct::long((1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)), 1);
+    }
+
+    parse_raw! {
+        tokens_of { @raw PREDICATES_CONST_ARG } <const LEN: usize> Wrapper<{ LEN }>
+        where [u8; LEN]: Tr<{ LEN * 2 }>, Wrapper<{ LEN }>: Clone { }
+    }
+
+    parse_impl! {
+        tokens_of { IMPL_CONST_ARG }
+        impl<const LEN: usize> Tr<{ LEN }> for Wrapper<[u8; LEN]> where [u8; LEN]: Into<Box<[u8]>> { }
+    }
+
+    concat! { tokens_of { CONCAT_SHIFT } [<T: Into<Box<u8>>>] [<T>] [], [<U: Into<<U as Iterator>::Item>>] [<U>] [] }
+
+    #[test]
+    fn run_brace_const_args() {
+        assert_tokens_eq(&[PREDICATES_CONST_ARG[4], IMPL_CONST_ARG, CONCAT_SHIFT], &[
+            stringify!([[[[u8; LEN]] [Tr<{ LEN * 2 }>]] [[Wrapper<{ LEN }>] [Clone]]]),
+            stringify!(
+                [] [<const LEN: usize>] [<LEN>] [where [u8; LEN]: Into<Box<[u8]>>]
+                [] [Tr<{ LEN }>] [Wrapper<[u8; LEN]>] { }
+            ),
+            stringify!([<T: Into<Box<u8> >, U: Into<< U as Iterator>::Item> >] [<T, U>] []),
+        ]);
+    }
+
+    parse! { tokens_of { UNTIL_EQ } until [=] <T: Iterator<Item = u8> = Vec<u8>> where T: Clone = Vec<T>; }
+
+    parse! { tokens_of { UNTIL_ARROW } until [=> in] <const LEN: usize> Wrapper<{ LEN }> where [u8; LEN]: Clone => { } }
+
+    parse! { tokens_of { UNTIL_BODY } until [=] <T> where T: Clone { x = y } }
+
+    parse! { tokens_of { UNTIL_KEYWORD } until [with] <T> (T) where T: Clone with x }
+
+    impl TestStruct {
+        parse! { impl_fn { first (t: &[T]) -> Option<&T> } until [=>] <T> where T: Clone => { t.first() } }
+    }
+
+    #[test]
+    fn run_parse_until() {
+        assert_tokens_eq(
+            &[UNTIL_EQ, UNTIL_ARROW, UNTIL_BODY, UNTIL_KEYWORD],
+            &[
+                stringify!([<T: Iterator<Item = u8> >] [<T>] [where T: Clone] = Vec<T>;),
+                stringify!([<const LEN: usize>] [<LEN>] [where [u8; LEN]: Clone] Wrapper<{ LEN }> => { }),
+                stringify!([<T>] [<T>] [where T: Clone] { x = y }),
+                stringify!([<T>] [<T>] [where T: Clone] (T) with x),
+            ]
+        );
+        assert_eq!(TestStruct::first(&[1, 2]), Some(&1));
+    }
+
+    parse! { tokens_of { ERROR_NONE } else tokens_of { ERROR_NONE_ERROR } <T> where T: Clone; }
+
+    parse! { tokens_of { ERROR_UNEXPECTED_OK } else tokens_of { ERROR_UNEXPECTED } <T, 'a + U> }
+
+    parse! { tokens_of { ERROR_CONST_TYPE_OK } else tokens_of { ERROR_CONST_TYPE } until [=] <T, const N> = () }
+
+    parse! { tokens_of { ERROR_COLON_OK } else tokens_of { ERROR_COLON } <T: Clone, U> where U: Send, T Copy; }
+
+    parse_raw! { tokens_of { ERROR_RAW_OK } else tokens_of { ERROR_RAW } where Vec<T: Clone; }
+
+    parse! { tokens_of { ERROR_UNCLOSED_OK } else tokens_of { ERROR_UNCLOSED } <T, U: Clone }
+
+    parse! { tokens_of { ERROR_NESTED_OK } else tokens_of { ERROR_NESTED } <T, U: Into<u8> = Vec<Option<u8 }
+
+    #[test]
+    fn run_parse_error_callback() {
+        assert_tokens_eq(
+            &[ERROR_NONE, ERROR_UNEXPECTED, ERROR_CONST_TYPE, ERROR_COLON, ERROR_RAW, ERROR_UNCLOSED, ERROR_NESTED],
+            &[
+                stringify!([<T>] [<T>] [where T: Clone];),
+                stringify!(
+                    [unexpected_token] [+] [[[T]]]
+                    [$crate::std_concat!("unexpected token '", $crate::std_stringify!(+), "', expected ':', '=', ',', or '>'")] []
+                ),
+                stringify!(
+                    [missing_const_type] [N] [[[T]]]
+                    [$crate::std_concat!("missing type for const parameter '", $crate::std_stringify!(N), "'")] [= ()]
+                ),
+                stringify!(
+                    [missing_colon] [T Copy] [[[T: Clone] [U]]]
+                    [$crate::std_concat!("missing ':' after '", $crate::std_stringify!(T Copy), "'")] [where U: Send, ;]
+                ),
+                stringify!(
+                    [missing_angle_bracket] [Vec<T: Clone;] []
+                    [$crate::std_concat!("missing '>' after '", $crate::std_stringify!(Vec<T: Clone;), "'")] [where]
+                ),
+                stringify!(
+                    [missing_angle_bracket] [U: Clone] [[[T]]]
+                    [$crate::std_concat!("missing '>' after '", $crate::std_stringify!(<T, U: Clone), "'")] []
+                ),
+                stringify!(
+                    [missing_angle_bracket] [U: Into<u8> = Vec<Option<u8] [[[T]]]
+                    [$crate::std_concat!("missing '>' after '", $crate::std_stringify!(<T, U: Into<u8> = Vec<Option<u8), "'")] []
+                ),
+            ]
+        );
+    }
+
+    macro_rules! recovered {
+        (
+            $name:ident $errors:ident [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] { $($field:tt)* } $($error:tt)*
+        ) => {
+            struct $name $($g)* $($w)* { $($field)* }
+            const $errors: &str = stringify!($($error)*);
+        };
+        (
+            $name:ident $errors:ident [$($g:tt)*] [$($r:tt)*] [$($w:tt)*] $($error:tt)*
+        ) => {
+            struct $name $($g)* $($w)*;
+            const $errors: &str = stringify!($($error)*);
+        };
+    }
+
+    parse! { recovered { RecoveredGenerics GENERICS_ERRORS } recover <'a, T: Clone, U, +> where T: Copy { t: &'a T, u: U } }
+
+    parse! { recovered { RecoveredWhere WHERE_ERRORS } recover <T> where T Copy, T: Clone { t: T } }
+
+    parse! { recovered { RecoveredBoth BOTH_ERRORS } recover <T, +> where T Copy { t: T } }
+
+    parse! { recovered { RecoveredFirst FIRST_ERRORS } recover <3> { t: u8 } }
+
+    parse! { recovered { RecoveredUnclosed UNCLOSED_ERRORS } recover <T: Clone ; }
+
+    parse! {
+        recovered { RecoveredPredicates PREDICATES_ERRORS }
+        recover <T, U, V> where T: Clone, U Copy, V: Send { t: T, u: U, v: V }
+    }
+
+    #[test]
+    fn run_parse_recover() {
+        let generics = RecoveredGenerics { t: &1, u: "u" };
+        assert_eq!((*generics.t, generics.u), (1, "u"));
+        assert_eq!(RecoveredWhere { t: 2 }.t, 2);
+        assert_eq!(RecoveredBoth { t: 3 }.t, 3);
+        assert_eq!(GENERICS_ERRORS.matches("compile_error").count(), 1);
+        assert!(GENERICS_ERRORS.contains("unexpected token '"));
+        assert_eq!(WHERE_ERRORS.matches("compile_error").count(), 1);
+        assert!(WHERE_ERRORS.contains("missing ':' after '"));
+        assert_eq!(BOTH_ERRORS.matches("compile_error").count(), 2);
+        assert_eq!(BOTH_ERRORS.matches("error_at_impl").count(), 2);
+        assert_eq!(RecoveredFirst { t: 4 }.t, 4);
+        assert!(FIRST_ERRORS.contains("unexpected token '"));
+        let _ = RecoveredUnclosed;
+        assert!(UNCLOSED_ERRORS.contains("missing '>' after '"));
+        let predicates = RecoveredPredicates { t: 'a', u: 1u8, v: "v" };
+        assert_eq!((predicates.t, predicates.u, predicates.v), ('a', 1, "v"));
+        assert_eq!(PREDICATES_ERRORS.matches("compile_error").count(), 1);
+    }
 }